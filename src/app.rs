@@ -1,42 +1,696 @@
 //! This file contains the front end app built using eframe and egui
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::SystemTime;
+
 use eframe::egui;
 
-use rand::Rng;
+mod actions;
+use actions::Action;
+
+mod archive;
+use archive::ArchiveAction;
+
+mod board_view;
+use board_view::{BoardView, Layout};
+
+mod kanban;
+
+mod legacy;
+use legacy::LegacyListWindow;
 
 mod list;
-use list::ListWindow;
 
 mod topbar;
 use topbar::TopBar;
 
 mod settings;
+use settings::Settings;
+
+mod tags;
+
+mod search;
+
+mod stats;
+
+mod theme;
+
+mod reminders;
+use reminders::ReminderAction;
 
-use crate::task::{List, Task};
+mod shortcuts;
+use shortcuts::{Selection, ShortcutAction};
+
+mod palette;
+use palette::Palette;
+
+use crate::board::{Board, RunningTimer};
+use crate::id::{ListId, TaskId};
+use crate::reminders::DueReminder;
+use crate::storage;
+use crate::task::{Task, TaskFilter};
+use crate::theme::Theme;
+use crate::{markdown, timelog};
 
 /// Constant for the default pixels_per_point
 const DEFAULT_PIXELS_PER_POINT: f32 = 1.5;
 
+/// How often the app wakes up to check for reminders while nothing else is happening
+const REMINDER_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// How often the topbar's timer is redrawn while a task is being timed
+const TIMER_REPAINT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(1);
+
+/// The shortcut for undoing the last change to the current board
+const UNDO_SHORTCUT: egui::KeyboardShortcut =
+    egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Z);
+/// The shortcut for redoing the last undone change to the current board
+const REDO_SHORTCUT: egui::KeyboardShortcut = egui::KeyboardShortcut::new(
+    egui::Modifiers::COMMAND.plus(egui::Modifiers::SHIFT),
+    egui::Key::Z,
+);
+
 #[derive(serde::Deserialize, serde::Serialize)]
 #[serde(default)]
 pub struct RustyTaskboardApp {
     topbar: TopBar,
-    list_windows: Vec<ListWindow>,
+    /// The boards, there is always at least one
+    boards: Vec<BoardView>,
+    /// The position of the board being displayed
+    current_board: usize,
+    settings: Settings,
+    /// The list windows saved by versions from before boards, which are moved onto a board
+    #[serde(rename = "list_windows", skip_serializing)]
+    legacy_list_windows: Vec<LegacyListWindow>,
+    /// Whether the saved app state couldn't be loaded, in which case it is never saved over
+    #[serde(skip)]
+    unreadable_state: bool,
+    /// A storage file that exists but couldn't be loaded, which is never saved over
+    #[serde(skip)]
+    unreadable_storage: Option<PathBuf>,
+    /// When the storage file was written, as of when the app last loaded or saved it
+    #[serde(skip)]
+    storage_modified: Option<SystemTime>,
+    /// The boards as of when the app last loaded or saved the storage file, or None if it hasn't
+    #[serde(skip)]
+    stored_boards: Option<Vec<Board>>,
+    /// The reminders that have been sent as desktop notifications, keyed by when they went off so
+    /// that snoozed reminders are sent again
+    #[serde(skip)]
+    notified: HashSet<(TaskId, chrono::NaiveDateTime)>,
+    /// Whether the shortcut cheat sheet is open
+    #[serde(skip)]
+    show_cheat_sheet: bool,
+    /// The task the user is being asked to confirm deleting
+    #[serde(skip)]
+    deleting_task: Option<(ListId, TaskId)>,
+    #[serde(skip)]
+    palette: Palette,
+    /// The user's themes, loaded from the themes file
+    #[serde(skip)]
+    themes: Vec<Theme>,
 }
 
 impl RustyTaskboardApp {
     pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
-        // Setting lightmode
-        cc.egui_ctx.set_visuals(egui::Visuals::light());
         // Setting the default pixels_per_point
         cc.egui_ctx.set_pixels_per_point(DEFAULT_PIXELS_PER_POINT);
 
         // Load previous app state (if any).
         // Note that you must enable the `persistence` feature for this to work.
-        if let Some(storage) = cc.storage {
-            return eframe::get_value(storage, eframe::APP_KEY).unwrap_or_default();
+        let state = cc
+            .storage
+            .and_then(|storage| storage.get_string(eframe::APP_KEY));
+        let mut app: Self = match state.map(|state| ron::from_str(&state)) {
+            Some(Ok(app)) => app,
+            Some(Err(error)) => {
+                eprintln!(
+                    "Couldn't load the app's state, so it won't be saved over: {}",
+                    error
+                );
+                Self {
+                    unreadable_state: true,
+                    ..Self::default()
+                }
+            }
+            None => Self::default(),
+        };
+        // State saved before boards has no boards, so they were filled in with the sample board
+        let legacy_list_windows = std::mem::take(&mut app.legacy_list_windows);
+        if !legacy_list_windows.is_empty() {
+            app.boards = vec![legacy::into_board(legacy_list_windows)];
+        }
+        if app.boards.is_empty() {
+            app = Self::default();
+        }
+        app.current_board = app.current_board.min(app.boards.len() - 1);
+
+        // The settings weren't loaded, so the storage file picked in them isn't known
+        if !app.unreadable_state {
+            app.share_storage_path();
+        }
+        app.load_storage();
+        app.load_themes();
+        app.apply_theme(&cc.egui_ctx, cc.integration_info.system_theme);
+        app
+    }
+
+    /// Replaces the user's themes with the ones in the themes file, if it exists
+    fn load_themes(&mut self) {
+        let Some(path) = crate::theme::themes_path() else {
+            return;
+        };
+
+        match crate::theme::load(&path) {
+            Ok(themes) => self.themes = themes,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => self.themes.clear(),
+            Err(error) => eprintln!("Couldn't load themes from {}: {}", path.display(), error),
+        }
+    }
+
+    /// Draws the app in the theme picked in the settings
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`          - The egui handle
+    /// * `system_theme` - The theme the desktop is using, or None if the platform doesn't report
+    ///   one, in which case "Follow system" draws the light theme
+    fn apply_theme(&self, ctx: &egui::Context, system_theme: Option<eframe::Theme>) {
+        let system_dark = system_theme.map(|system_theme| system_theme == eframe::Theme::Dark);
+        let theme = crate::theme::resolve(self.settings.theme(), &self.themes, system_dark);
+        theme::apply(ctx, &theme);
+    }
+
+    /// Replaces the boards with the ones in the storage file, if it exists
+    fn load_storage(&mut self) {
+        let Some(path) = self.settings.storage_path() else {
+            return;
+        };
+
+        // Taken before reading, so that a write while reading is picked up by the next reload
+        self.storage_modified = storage::modified(&path).ok();
+        match storage::load(&path) {
+            Ok(boards) => {
+                if !boards.is_empty() {
+                    self.set_boards(boards);
+                    self.stored_boards = Some(self.boards());
+                }
+                self.unreadable_storage = None;
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => (),
+            Err(error) => {
+                eprintln!("Couldn't load boards from {}: {}", path.display(), error);
+                self.unreadable_storage = Some(path);
+            }
+        }
+    }
+
+    /// Reloads the storage file if something else, such as the command line interface, has
+    /// written to it since the app last loaded or saved it
+    fn reload_changed_storage(&mut self) {
+        let Some(path) = self.settings.storage_path() else {
+            return;
+        };
+
+        let modified = storage::modified(&path).ok();
+        if modified.is_some() && modified != self.storage_modified {
+            self.load_storage();
+        }
+    }
+
+    /// Whether the boards are the same as when the app last loaded or saved the storage file
+    fn boards_stored(&self) -> bool {
+        self.stored_boards.as_ref().is_some_and(|stored| {
+            stored
+                .iter()
+                .eq(self.boards.iter().map(|board| board.board()))
+        })
+    }
+
+    /// Saves the boards to the storage file, unless it has changed since the app last loaded or
+    /// saved it, in which case it is reloaded first so that the changes aren't saved over
+    ///
+    /// # Returns
+    ///
+    /// Whether the boards were written to the storage file
+    fn save_storage(&mut self) -> bool {
+        self.reload_changed_storage();
+
+        // Kept even if saving fails, so that it is only tried again once the boards change
+        let boards = self.boards();
+        self.stored_boards = Some(boards.clone());
+
+        let Some(path) = self.settings.storage_path() else {
+            return false;
+        };
+
+        if self.unreadable_storage.as_ref() == Some(&path) {
+            eprintln!(
+                "Not saving boards to {} as it couldn't be loaded and would be overwritten",
+                path.display()
+            );
+            return false;
+        }
+
+        match storage::save(&path, &boards) {
+            Ok(()) => {
+                self.storage_modified = storage::modified(&path).ok();
+                true
+            }
+            Err(error) => {
+                eprintln!("Couldn't save boards to {}: {}", path.display(), error);
+                false
+            }
+        }
+    }
+
+    /// Moves the boards to another storage file. The boards are saved to the current file first,
+    /// then replaced with the boards in the new file if it exists, or kept to be saved to it if it
+    /// doesn't
+    fn set_storage_path(&mut self, path: String) {
+        self.save_storage();
+        self.settings.set_storage_path(path);
+        self.share_storage_path();
+        self.load_storage();
+    }
+
+    /// Writes the storage file picked in the settings where the command line interface finds it,
+    /// so that both use the same file
+    fn share_storage_path(&self) {
+        let Some(file) = storage::configured_path_file() else {
+            return;
+        };
+
+        let path = self.settings.picked_storage_path();
+        if storage::load_configured_path(&file).ok() == Some(path.clone()) {
+            return;
+        }
+        if let Err(error) = storage::save_configured_path(&file, path.as_deref()) {
+            eprintln!(
+                "Couldn't save the storage path to {}: {}",
+                file.display(),
+                error
+            );
+        }
+    }
+
+    /// Replaces the boards in the app, keeping the windows of boards and lists that still exist
+    /// and staying on the current board if it still exists. The undo history is forgotten, as it
+    /// could undo changes made elsewhere
+    fn set_boards(&mut self, boards: Vec<Board>) {
+        let current = self.board().id();
+        let mut views = std::mem::take(&mut self.boards);
+        self.boards = boards
+            .into_iter()
+            .map(|board| {
+                // Boards saved before they had identifiers are matched by name
+                let found = views
+                    .iter()
+                    .position(|view| view.board().id() == board.id())
+                    .or_else(|| views.iter().position(|view| view.name() == board.name()));
+                match found {
+                    Some(index) => {
+                        let mut view = views.remove(index);
+                        view.reload(board);
+                        view
+                    }
+                    None => BoardView::from_board(board),
+                }
+            })
+            .collect();
+        self.current_board = self
+            .boards
+            .iter()
+            .position(|view| view.board().id() == current)
+            .unwrap_or(self.current_board)
+            .min(self.boards.len() - 1);
+    }
+
+    /// The board currently displayed by the app
+    pub fn board(&self) -> &Board {
+        self.current().board()
+    }
+
+    /// Clones all the boards in the app
+    pub fn boards(&self) -> Vec<Board> {
+        self.boards
+            .iter()
+            .map(|board| board.board().clone())
+            .collect()
+    }
+
+    /// The board being displayed
+    fn current(&self) -> &BoardView {
+        let index = self.current_board.min(self.boards.len() - 1);
+        &self.boards[index]
+    }
+
+    /// A mutable reference to the board being displayed
+    fn current_mut(&mut self) -> &mut BoardView {
+        let index = self.current_board.min(self.boards.len() - 1);
+        &mut self.boards[index]
+    }
+
+    /// Shows the list window of a task and highlights and selects the task, clearing the tag
+    /// filter if it hides the list and the list's filter if it hides the task
+    fn go_to_task(&mut self, ctx: &egui::Context, list: ListId, task: TaskId) {
+        let Some(matches_tags) = self
+            .board()
+            .list_by_id(list)
+            .map(|found| found.matches_tags(self.topbar.active_tags()))
+        else {
+            return;
+        };
+
+        let now = chrono::Local::now().naive_local();
+        let filtered_out = !self.current().shown_tasks(list, now).contains(&task);
+        if let Some(list_window) = self.current_mut().list_window_mut(list) {
+            *list_window.mut_visible() = true;
+            if filtered_out {
+                list_window.set_filter(TaskFilter::All);
+            }
+        }
+        if !matches_tags {
+            self.topbar.clear_active_tags();
+        }
+        ctx.move_to_top(egui::LayerId::new(egui::Order::Middle, egui::Id::new(list)));
+        search::highlight(ctx, task);
+        shortcuts::set_selection(
+            ctx,
+            Selection {
+                list: Some(list),
+                task: Some(task),
+            },
+        );
+    }
+
+    /// The task being timed on any board, along with the position of its board
+    fn running_timer(&self) -> Option<(usize, RunningTimer)> {
+        self.boards
+            .iter()
+            .enumerate()
+            .filter_map(|(index, board)| Some((index, board.running_timer()?)))
+            .max_by_key(|(_, timer)| timer.started_at)
+    }
+
+    /// The reminders that have gone off on every board, with the name of the board they are on
+    fn due_reminders(&self, now: chrono::NaiveDateTime) -> Vec<(String, DueReminder)> {
+        self.boards
+            .iter()
+            .flat_map(|board| {
+                crate::reminders::due_reminders(board.board(), now)
+                    .into_iter()
+                    .map(|reminder| (board.name(), reminder))
+            })
+            .collect()
+    }
+
+    /// Sends the reminders that haven't been sent yet as desktop notifications, if enabled
+    fn notify_desktop(&mut self, reminders: &[(String, DueReminder)]) {
+        for (board_name, reminder) in reminders {
+            if !self.notified.insert((reminder.task, reminder.goes_off_at)) {
+                continue;
+            }
+            if !self.settings.desktop_notifications() {
+                continue;
+            }
+            let body = format!("{} on {}", reminder.title, board_name);
+            crate::reminders::spawn_desktop_notification("Reminder".to_string(), body);
         }
+    }
+
+    /// Applies an action the user took on a reminder toast
+    fn apply_reminder_action(&mut self, action: ReminderAction, now: chrono::NaiveDateTime) {
+        let (reminder, snooze) = match action {
+            ReminderAction::Snooze(reminder, length) => (reminder, Some(length)),
+            ReminderAction::Dismiss(reminder) => (reminder, None),
+        };
+        for board in &mut self.boards {
+            if let Some(found) = board.reminder_mut(reminder.list, reminder.task) {
+                match snooze {
+                    Some(length) => found.snooze(now + length),
+                    None => found.dismiss(),
+                }
+            }
+        }
+    }
+
+    /// Selects the list whose window the user clicked on, so that shortcuts act on it
+    fn select_clicked_list(&self, ctx: &egui::Context) {
+        let pressed_at = ctx.input(|input| match input.pointer.any_pressed() {
+            true => input.pointer.interact_pos(),
+            false => None,
+        });
+        let Some(layer) = pressed_at.and_then(|pos| ctx.layer_id_at(pos)) else {
+            return;
+        };
+
+        let clicked = self
+            .board()
+            .lists()
+            .iter()
+            .map(|list| list.id())
+            .find(|list| layer.id == egui::Id::new(*list));
+        if let Some(list) = clicked {
+            let selection = shortcuts::selection(ctx);
+            if selection.list != Some(list) {
+                shortcuts::set_selection(
+                    ctx,
+                    Selection {
+                        list: Some(list),
+                        task: None,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Applies a keyboard shortcut the user pressed
+    fn apply_shortcut(&mut self, ctx: &egui::Context, action: ShortcutAction) {
+        let global_action = match action {
+            ShortcutAction::OpenSettings => Some(Action::ToggleSettings),
+            ShortcutAction::CheatSheet => Some(Action::ToggleCheatSheet),
+            ShortcutAction::CommandPalette => Some(Action::OpenCommandPalette),
+            _ => None,
+        };
+        if let Some(global_action) = global_action {
+            self.apply_action(ctx, global_action);
+            return;
+        }
+
+        let now = chrono::Local::now().naive_local();
+        let lists = self.current().shown_lists(self.topbar.active_tags());
+        let mut selection = shortcuts::selection(ctx);
+        // A selection that is no longer shown falls back to the first list
+        if !selection.list.is_some_and(|list| lists.contains(&list)) {
+            selection = Selection {
+                list: lists.first().copied(),
+                task: None,
+            };
+        }
+        let Some(mut list) = selection.list else {
+            return;
+        };
+        let tasks = self.current().shown_tasks(list, now);
+        let task = selection.task.filter(|task| tasks.contains(task));
+
+        match action {
+            ShortcutAction::NewTask => self.apply_action(ctx, Action::FocusNewTask(list)),
+            ShortcutAction::ToggleCompleted => {
+                if let Some(task) = task {
+                    self.current_mut().toggle_completed(list, task, now);
+                }
+            }
+            ShortcutAction::NextTask | ShortcutAction::PreviousTask => {
+                let forward = action == ShortcutAction::NextTask;
+                selection.task = shortcuts::step(&tasks, task, forward);
+            }
+            ShortcutAction::NextList | ShortcutAction::PreviousList => {
+                let forward = action == ShortcutAction::NextList;
+                list = shortcuts::step(&lists, Some(list), forward).unwrap_or(list);
+                selection.list = Some(list);
+                selection.task = self.current().shown_tasks(list, now).first().copied();
+                ctx.move_to_top(egui::LayerId::new(egui::Order::Middle, egui::Id::new(list)));
+            }
+            ShortcutAction::ToggleEditing => self.apply_action(ctx, Action::ToggleEditing(list)),
+            ShortcutAction::DeleteTask => {
+                self.deleting_task = task.map(|task| (list, task));
+            }
+            ShortcutAction::OpenSettings
+            | ShortcutAction::CheatSheet
+            | ShortcutAction::CommandPalette => (),
+        }
+        shortcuts::set_selection(ctx, selection);
+    }
+
+    /// Asks the user to confirm deleting the task picked with the delete shortcut, and deletes it
+    /// if they do
+    fn draw_confirm_delete_task(&mut self, ctx: &egui::Context) {
+        let Some((list, task)) = self.deleting_task else {
+            return;
+        };
+        let Some(title) = self
+            .board()
+            .list_by_id(list)
+            .and_then(|found| found.task_by_id(task))
+            .map(|task| task.title())
+        else {
+            self.deleting_task = None;
+            return;
+        };
+
+        match shortcuts::draw_confirm_delete_task(ctx, &title) {
+            Some(true) => {
+                // The next task down is selected in place of the deleted one, or the one above it
+                // if it was the last task
+                let tasks = self
+                    .current()
+                    .shown_tasks(list, chrono::Local::now().naive_local());
+                let next = [true, false]
+                    .into_iter()
+                    .filter_map(|forward| shortcuts::step(&tasks, Some(task), forward))
+                    .find(|next| *next != task);
+                self.current_mut().delete_task(list, task);
+                shortcuts::set_selection(
+                    ctx,
+                    Selection {
+                        list: Some(list),
+                        task: next,
+                    },
+                );
+                self.deleting_task = None;
+            }
+            Some(false) => self.deleting_task = None,
+            None => (),
+        }
+    }
 
-        Default::default()
+    /// Applies an action dispatched by a button or run from the command palette
+    fn apply_action(&mut self, ctx: &egui::Context, action: Action) {
+        let now = chrono::Local::now().naive_local();
+        match action {
+            Action::AddList(list) => self.current_mut().add_list(list),
+            Action::DeleteList(list) => self.current_mut().remove_list(list),
+            Action::RenameList(list, name) => {
+                if let Some(list) = self.current_mut().list_mut(list) {
+                    list.set_name(name);
+                }
+            }
+            Action::SetListTags(list, tags) => {
+                if let Some(list) = self.current_mut().list_mut(list) {
+                    *list.tags_mut() = tags;
+                }
+            }
+            Action::SetListVisible(list, visible) => {
+                if let Some(list_window) = self.current_mut().list_window_mut(list) {
+                    *list_window.mut_visible() = visible;
+                }
+            }
+            Action::ToggleEditing(list) => {
+                if let Some(list_window) = self.current_mut().list_window_mut(list) {
+                    list_window.toggle_editing();
+                }
+            }
+            Action::FocusNewTask(list) => {
+                if let Some(list_window) = self.current_mut().list_window_mut(list) {
+                    *list_window.mut_visible() = true;
+                }
+                ctx.move_to_top(egui::LayerId::new(egui::Order::Middle, egui::Id::new(list)));
+                ctx.memory_mut(|memory| memory.request_focus(list::add_task_id(list)));
+            }
+            Action::SortList(list, order) => {
+                if let Some(list) = self.current_mut().list_mut(list) {
+                    list.sort(order);
+                }
+            }
+            Action::ArchiveCompleted(list) => self.current_mut().archive_completed(list, now),
+            Action::DeleteCompleted(list) => {
+                if let Some(list) = self.current_mut().list_mut(list) {
+                    list.remove_completed();
+                }
+            }
+            Action::CopyList(list) => {
+                if let Some(list) = self.board().list_by_id(list) {
+                    let contents = markdown::export_list(list);
+                    ctx.output_mut(|output| output.copied_text = contents);
+                }
+            }
+            Action::GoToTask(list, task) => self.go_to_task(ctx, list, task),
+            Action::SwitchBoard(index) => {
+                self.current_board = index.min(self.boards.len() - 1);
+            }
+            Action::AddBoard(name) => {
+                self.boards.push(BoardView::from_board(Board::new(name)));
+                self.current_board = self.boards.len() - 1;
+            }
+            Action::RenameBoard(name) => self.current_mut().set_name(name),
+            Action::SetLayout(layout) => self.current_mut().set_layout(layout),
+            Action::DeleteBoard => {
+                if self.boards.len() > 1 {
+                    let index = self.current_board.min(self.boards.len() - 1);
+                    self.boards.remove(index);
+                    self.current_board = index.saturating_sub(1);
+                }
+            }
+            Action::Import(path, format) => match fs::read_to_string(&path) {
+                Ok(contents) => {
+                    for list in format.import(&contents) {
+                        self.current_mut().add_list(list);
+                    }
+                }
+                Err(error) => eprintln!("Couldn't import {}: {}", path.display(), error),
+            },
+            Action::Export(path, format) => {
+                if let Err(error) = fs::write(&path, format.export(self.board())) {
+                    eprintln!("Couldn't export to {}: {}", path.display(), error);
+                }
+            }
+            Action::CopyBoard(format) => {
+                let contents = format.export(self.board());
+                ctx.output_mut(|output| output.copied_text = contents);
+            }
+            Action::ExportTimeLog(path) => {
+                if let Err(error) = fs::write(&path, timelog::export_csv(self.board(), now)) {
+                    eprintln!(
+                        "Couldn't export the time log to {}: {}",
+                        path.display(),
+                        error
+                    );
+                }
+            }
+            Action::StopTimer => {
+                for board in &mut self.boards {
+                    board.stop_timer(now);
+                }
+            }
+            Action::ToggleSettings => {
+                let shown = self.topbar.show_settings_mut();
+                *shown = !*shown;
+            }
+            Action::ToggleArchive => {
+                let shown = self.topbar.show_archive_mut();
+                *shown = !*shown;
+            }
+            Action::ToggleStats => {
+                let shown = self.topbar.show_stats_mut();
+                *shown = !*shown;
+            }
+            Action::ToggleCheatSheet => self.show_cheat_sheet = !self.show_cheat_sheet,
+            Action::OpenCommandPalette => self.palette.open(),
+            Action::SetShortcut(action, binding) => {
+                self.settings.shortcuts_mut().set_binding(action, binding)
+            }
+            Action::ResetShortcuts => self.settings.shortcuts_mut().reset(),
+            Action::SetHistoryDepth(depth) => self.settings.set_history_depth(depth),
+            Action::SetStoragePath(path) => self.set_storage_path(path),
+            Action::SetDesktopNotifications(enabled) => {
+                self.settings.set_desktop_notifications(enabled)
+            }
+            Action::SetTheme(theme) => self.settings.set_theme(theme),
+            Action::ReloadThemes => self.load_themes(),
+        }
     }
 }
 
@@ -59,25 +713,27 @@ impl Default for RustyTaskboardApp {
                 .build()
                 .unwrap(),
         ];
-        let mut list = List::new();
+        let mut board = Board::new("Main".to_string());
+        let list = board.create_list("Tasklist".to_string());
         for task in tasks {
             list.add(task);
         }
+        board.create_list("Second List".to_string());
         Self {
             topbar: TopBar::default(),
-            list_windows: vec![
-                ListWindow::builder()
-                    .name("Tasklist".to_string())
-                    .id(egui::Id::new(rand::thread_rng().gen_range(0..u64::MAX)))
-                    .list(list)
-                    .build()
-                    .unwrap(),
-                ListWindow::builder()
-                    .name("Second List".to_string())
-                    .id(egui::Id::new(rand::thread_rng().gen_range(0..u64::MAX)))
-                    .build()
-                    .unwrap(),
-            ],
+            boards: vec![BoardView::from_board(board)],
+            current_board: 0,
+            settings: Settings::default(),
+            legacy_list_windows: vec![],
+            unreadable_state: false,
+            unreadable_storage: None,
+            storage_modified: None,
+            stored_boards: None,
+            notified: HashSet::new(),
+            show_cheat_sheet: false,
+            deleting_task: None,
+            palette: Palette::default(),
+            themes: vec![],
         }
     }
 }
@@ -85,24 +741,152 @@ impl Default for RustyTaskboardApp {
 impl eframe::App for RustyTaskboardApp {
     /// Called by the frame work to save state before shutdown.
     fn save(&mut self, storage: &mut dyn eframe::Storage) {
+        let saved = self.save_storage();
+        if self.unreadable_state {
+            return;
+        }
+
+        // Once the boards are in the storage file only the windows displaying them are saved with
+        // the app's state, so that the file is the one place the boards are kept
+        let boards: Vec<Board> = match saved {
+            true => self
+                .boards
+                .iter_mut()
+                .map(|view| {
+                    let contents = view.board().without_contents();
+                    std::mem::replace(view.board_mut(), contents)
+                })
+                .collect(),
+            false => vec![],
+        };
         eframe::set_value(storage, eframe::APP_KEY, self);
+        for (view, board) in self.boards.iter_mut().zip(boards) {
+            *view.board_mut() = board;
+        }
     }
 
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+    fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        self.apply_theme(ctx, frame.info().system_theme);
+        self.reload_changed_storage();
+
+        // Text fields handle their own undo and redo while focused
+        if ctx.memory(|memory| memory.focus()).is_none() {
+            if ctx.input_mut(|input| input.consume_shortcut(&REDO_SHORTCUT)) {
+                self.current_mut().redo();
+            }
+            if ctx.input_mut(|input| input.consume_shortcut(&UNDO_SHORTCUT)) {
+                self.current_mut().undo();
+            }
+        }
+        self.select_clicked_list(ctx);
+        for action in shortcuts::read_shortcuts(ctx, self.settings.shortcuts()) {
+            self.apply_shortcut(ctx, action);
+        }
+
         egui::CentralPanel::default().show(ctx, |ui| {
-            self.list_windows = self
-                .list_windows
-                .iter()
-                .filter_map(|list_window| list::draw_list_window(ctx, list_window.clone()))
-                .collect();
+            let active_tags = self.topbar.active_tags().clone();
+            let board = self.current_mut();
+            let layout = board.layout();
+            let mut task_drop = None;
+            if layout == Layout::Windows {
+                for (list, list_window) in board.lists_mut() {
+                    if list.matches_tags(&active_tags) {
+                        let dropped = list::draw_list_window(ctx, list, list_window);
+                        task_drop = task_drop.or(dropped);
+                    }
+                }
+            }
+            // Tasks are moved once every window is drawn, as they can move between lists
+            if let Some(task_drop) = task_drop {
+                board.move_task(task_drop);
+            }
 
-            if let Some(list_to_add) = self.topbar.draw(ui) {
-                self.list_windows.push(list_to_add);
+            let tags = self.board().list_tags();
+            let board_names: Vec<String> = self.boards.iter().map(|board| board.name()).collect();
+            let timer = self.running_timer();
+            self.topbar.draw(
+                ui,
+                &tags,
+                &board_names,
+                self.current_board,
+                layout,
+                timer.as_ref().map(|(board, timer)| (*board, timer)),
+            );
+
+            let query = self.topbar.search().trim().to_string();
+            if !query.is_empty() {
+                let matches =
+                    crate::search::search(self.board(), &query, self.topbar.search_options());
+                if let Some(found) = search::draw_search_results(ctx, &matches) {
+                    self.apply_action(ctx, Action::GoToTask(found.list, found.task));
+                }
+            }
+
+            if layout == Layout::Kanban {
+                let shown_lists = self.current().shown_lists(&active_tags);
+                kanban::draw_kanban(ui, &mut self.current_mut().lists_mut(), &shown_lists);
+            }
+            list::draw_dragged_task(ctx);
+
+            let archive = self.current().archive().to_vec();
+            match archive::draw_archive(ctx, &archive, self.topbar.show_archive_mut()) {
+                Some(ArchiveAction::Restore(index)) => self.current_mut().restore_archived(index),
+                Some(ArchiveAction::Purge(index)) => self.current_mut().purge_archived(index),
+                Some(ArchiveAction::PurgeAll) => self.current_mut().purge_archive(),
+                None => (),
+            }
+
+            if self.topbar.show_stats() {
+                let today = chrono::Local::now().date_naive();
+                let stats = crate::stats::stats(self.board(), today);
+                stats::draw_stats(ctx, &stats, self.topbar.show_stats_mut());
             }
 
             if self.topbar.show_settings() {
-                self.list_windows = settings::draw_settings(ctx, &self.list_windows);
+                settings::draw_settings(ctx, self.current(), &self.settings, &self.themes);
             }
         });
+
+        let now = chrono::Local::now().naive_local();
+        let reminders = self.due_reminders(now);
+        self.notify_desktop(&reminders);
+        if let Some(action) = reminders::draw_reminders(ctx, &reminders) {
+            self.apply_reminder_action(action, now);
+        }
+        ctx.request_repaint_after(REMINDER_CHECK_INTERVAL);
+
+        shortcuts::draw_cheat_sheet(ctx, self.settings.shortcuts(), &mut self.show_cheat_sheet);
+        self.draw_confirm_delete_task(ctx);
+
+        if self.palette.is_open() {
+            let commands = actions::commands(self, now);
+            if let Some(action) = self.palette.draw(ctx, &commands) {
+                actions::dispatch(ctx, action);
+            }
+        }
+        for action in actions::take_dispatched(ctx) {
+            self.apply_action(ctx, action);
+        }
+
+        // Scheduled before tracking changes so that undoing a completion also removes the occurrence
+        self.current_mut().schedule_recurrences(now);
+        // Likewise undoing starting a timer restarts the one it stopped. Only one task is timed
+        // at once across every board
+        if let Some((_, running)) = self.running_timer() {
+            for board in &mut self.boards {
+                board.stop_timers_except(&running);
+            }
+            ctx.request_repaint_after(TIMER_REPAINT_INTERVAL);
+        }
+
+        let focus = ctx.memory(|memory| memory.focus());
+        let history_depth = self.settings.history_depth();
+        self.current_mut().track_changes(focus, history_depth);
+
+        // Written as soon as the boards change, so that the file is never behind the app when
+        // something else, such as the command line interface, writes to it
+        if !self.boards_stored() {
+            self.save_storage();
+        }
     }
 }
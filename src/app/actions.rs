@@ -0,0 +1,272 @@
+//! This module contains the actions the user can take, which the buttons of the app and the
+//! command palette dispatch to the app to be applied
+//!
+//! Buttons call [`dispatch`] rather than changing the board themselves, and the app applies every
+//! dispatched action once the frame has been drawn. [`commands`] lists the actions that can be
+//! run from the command palette, so anything a button can do can also be found by name
+use std::path::PathBuf;
+
+use chrono::NaiveDateTime;
+use egui::Context;
+
+use crate::app::board_view::Layout;
+use crate::app::shortcuts::{KeyBinding, ShortcutAction};
+use crate::app::RustyTaskboardApp;
+use crate::format::Format;
+use crate::id::{ListId, TaskId};
+use crate::task::{List, SortOrder, Tags};
+use crate::theme::ThemeChoice;
+
+/// The key the actions waiting to be applied are stored under in egui's memory
+const DISPATCHED_KEY: &str = "dispatched actions";
+
+/// Enum to model the actions the user can take
+#[derive(Clone)]
+pub enum Action {
+    /// Add the list to the current board
+    AddList(List),
+    /// Delete the list from the current board
+    DeleteList(ListId),
+    RenameList(ListId, String),
+    /// Replace the tags attached to a list
+    SetListTags(ListId, Tags),
+    /// Show or hide the window of a list
+    SetListVisible(ListId, bool),
+    /// Switch a list in and out of editing mode
+    ToggleEditing(ListId),
+    /// Put the cursor in the textbox for adding a task to a list
+    FocusNewTask(ListId),
+    SortList(ListId, SortOrder),
+    /// Move the completed tasks in a list into the archive
+    ArchiveCompleted(ListId),
+    /// Permanently remove the completed tasks in a list
+    DeleteCompleted(ListId),
+    /// Copy a list to the clipboard as markdown
+    CopyList(ListId),
+    /// Show the window of a task and highlight it
+    GoToTask(ListId, TaskId),
+    /// Switch to the board at the given position
+    SwitchBoard(usize),
+    /// Create a new board with the given name and switch to it
+    AddBoard(String),
+    /// Rename the current board
+    RenameBoard(String),
+    /// Delete the current board
+    DeleteBoard,
+    /// Change how the current board is laid out
+    SetLayout(Layout),
+    /// Add the lists in the file to the current board
+    Import(PathBuf, Format),
+    /// Write the current board to a file
+    Export(PathBuf, Format),
+    /// Copy the current board to the clipboard
+    CopyBoard(Format),
+    /// Write the time log of the current board to a CSV file
+    ExportTimeLog(PathBuf),
+    /// Stop timing the task being timed
+    StopTimer,
+    ToggleSettings,
+    ToggleArchive,
+    ToggleStats,
+    /// Show or hide the list of keyboard shortcuts
+    ToggleCheatSheet,
+    OpenCommandPalette,
+    /// Bind a keyboard shortcut to different keys
+    SetShortcut(ShortcutAction, KeyBinding),
+    /// Put every keyboard shortcut back on its default keys
+    ResetShortcuts,
+    /// Change how many changes can be undone on each board
+    SetHistoryDepth(usize),
+    /// Move the boards to a different storage file, an empty path uses the default file
+    SetStoragePath(String),
+    /// Turn sending reminders as desktop notifications on or off
+    SetDesktopNotifications(bool),
+    /// Draw the app in a different theme
+    SetTheme(ThemeChoice),
+    /// Load the user's themes from the themes file again, to pick up changes to it
+    ReloadThemes,
+}
+
+/// Queues an action to be applied by the app once the frame has been drawn
+///
+/// # Arguments
+///
+/// * `ctx`    - The egui handle
+/// * `action` - The action to apply
+pub fn dispatch(ctx: &Context, action: Action) {
+    ctx.data_mut(|data| {
+        data.get_temp_mut_or_default::<Vec<Action>>(egui::Id::new(DISPATCHED_KEY))
+            .push(action)
+    });
+}
+
+/// Takes the actions dispatched since this was last called, in the order they were dispatched
+pub fn take_dispatched(ctx: &Context) -> Vec<Action> {
+    ctx.data_mut(|data| {
+        std::mem::take(data.get_temp_mut_or_default::<Vec<Action>>(egui::Id::new(DISPATCHED_KEY)))
+    })
+}
+
+/// An action that can be run from the command palette, along with the name it is found by
+#[derive(Clone)]
+pub struct Command {
+    pub label: String,
+    pub action: Action,
+}
+
+impl Command {
+    fn new(label: String, action: Action) -> Self {
+        Self { label, action }
+    }
+}
+
+/// Builds the commands that can currently be run from the command palette
+///
+/// # Arguments
+///
+/// * `app` - The app, to find the boards, lists and tasks the commands can act on
+/// * `now` - The current time, used to skip the tasks hidden by a list's filter
+///
+/// # Returns
+///
+/// The commands, grouped by what they act on
+pub fn commands(app: &RustyTaskboardApp, now: NaiveDateTime) -> Vec<Command> {
+    let mut commands = vec![];
+    let board = app.current();
+
+    for list in board.board().lists() {
+        let id = list.id();
+        let name = list.name();
+        let visible = board.list_visible(id);
+        commands.push(Command::new(
+            format!("Add task to {}", name),
+            Action::FocusNewTask(id),
+        ));
+        let visibility = match visible {
+            true => format!("Hide list {}", name),
+            false => format!("Show list {}", name),
+        };
+        commands.push(Command::new(
+            visibility,
+            Action::SetListVisible(id, !visible),
+        ));
+        for order in SortOrder::ALL {
+            commands.push(Command::new(
+                format!("Sort {} {}", name, order.to_string().to_lowercase()),
+                Action::SortList(id, order),
+            ));
+        }
+        commands.push(Command::new(
+            format!("Edit list {}", name),
+            Action::ToggleEditing(id),
+        ));
+        commands.push(Command::new(
+            format!("Archive completed tasks in {}", name),
+            Action::ArchiveCompleted(id),
+        ));
+        commands.push(Command::new(
+            format!("Copy {} as Markdown", name),
+            Action::CopyList(id),
+        ));
+    }
+
+    for list in board.board().lists() {
+        let id = list.id();
+        for task in board.shown_tasks(id, now) {
+            if let Some(found) = list.task_by_id(task) {
+                commands.push(Command::new(
+                    format!("Go to task {}", found.title()),
+                    Action::GoToTask(id, task),
+                ));
+            }
+        }
+    }
+
+    let window_commands = [
+        (
+            "settings",
+            app.topbar.show_settings(),
+            Action::ToggleSettings,
+        ),
+        ("archive", app.topbar.show_archive(), Action::ToggleArchive),
+        ("stats", app.topbar.show_stats(), Action::ToggleStats),
+        ("shortcuts", app.show_cheat_sheet, Action::ToggleCheatSheet),
+    ];
+    for (window, shown, action) in window_commands {
+        let label = match shown {
+            true => format!("Close {}", window),
+            false => format!("Open {}", window),
+        };
+        commands.push(Command::new(label, action));
+    }
+    if app.running_timer().is_some() {
+        commands.push(Command::new("Stop timer".to_string(), Action::StopTimer));
+    }
+
+    for (index, name) in app.boards.iter().map(|board| board.name()).enumerate() {
+        if index != app.current_board {
+            commands.push(Command::new(
+                format!("Switch to board {}", name),
+                Action::SwitchBoard(index),
+            ));
+        }
+    }
+    for layout in Layout::ALL {
+        if layout != board.layout() {
+            commands.push(Command::new(
+                format!("Show board as {}", layout),
+                Action::SetLayout(layout),
+            ));
+        }
+    }
+
+    if let Some((path, format)) = app.topbar.file_target() {
+        commands.push(Command::new(
+            format!("Export board to {} as {}", path.display(), format),
+            Action::Export(path.clone(), format),
+        ));
+        commands.push(Command::new(
+            format!("Export time log to {}", path.display()),
+            Action::ExportTimeLog(path),
+        ));
+    }
+    for format in Format::ALL {
+        commands.push(Command::new(
+            format!("Copy board as {}", format),
+            Action::CopyBoard(format),
+        ));
+    }
+
+    let custom_themes = app
+        .themes
+        .iter()
+        .map(|theme| ThemeChoice::Custom(theme.name.clone()));
+    for theme in ThemeChoice::BUILT_IN.into_iter().chain(custom_themes) {
+        if &theme != app.settings.theme() {
+            commands.push(Command::new(
+                format!("Use theme {}", theme),
+                Action::SetTheme(theme),
+            ));
+        }
+    }
+    commands.push(Command::new(
+        "Reload themes".to_string(),
+        Action::ReloadThemes,
+    ));
+
+    let notifications = app.settings.desktop_notifications();
+    let label = match notifications {
+        true => "Turn off desktop notifications",
+        false => "Turn on desktop notifications",
+    };
+    commands.push(Command::new(
+        label.to_string(),
+        Action::SetDesktopNotifications(!notifications),
+    ));
+    commands.push(Command::new(
+        "Reset shortcuts".to_string(),
+        Action::ResetShortcuts,
+    ));
+
+    commands
+}
@@ -0,0 +1,79 @@
+//! This module contains the window for viewing the archive of completed tasks
+use egui::{Context, RichText};
+
+use crate::board::ArchivedTask;
+
+/// The format completion dates are shown in
+const DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Enum to model the actions the user can take in the archive window
+pub enum ArchiveAction {
+    /// Put the archived task at the given position back into its list
+    Restore(usize),
+    /// Permanently remove the archived task at the given position
+    Purge(usize),
+    /// Permanently remove every archived task
+    PurgeAll,
+}
+
+/// Draws the archive window, listing the archived tasks most recently completed first
+///
+/// # Arguments
+///
+/// * `ctx`     - The egui handle
+/// * `archive` - The archived tasks of the current board
+/// * `open`    - Whether the window is open, set to false if the user closes it
+///
+/// # Returns
+///
+/// The action the user took, if any
+pub fn draw_archive(
+    ctx: &Context,
+    archive: &[ArchivedTask],
+    open: &mut bool,
+) -> Option<ArchiveAction> {
+    let mut action = None;
+
+    egui::Window::new("Archive")
+        .open(open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            if archive.is_empty() {
+                ui.label("No tasks have been archived, use \"Clear Completed\" on a list");
+                return;
+            }
+
+            let mut order: Vec<usize> = (0..archive.len()).collect();
+            order.sort_by_key(|index| std::cmp::Reverse(archive[*index].completed_at()));
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for index in order {
+                    let archived = &archive[index];
+                    ui.horizontal(|ui| {
+                        if ui.button("Restore").clicked() {
+                            action = Some(ArchiveAction::Restore(index));
+                        }
+                        if ui.button("Purge").clicked() {
+                            action = Some(ArchiveAction::Purge(index));
+                        }
+                        ui.label(archived.task().title());
+                    });
+                    ui.label(
+                        RichText::new(format!(
+                            "From {}, completed {}",
+                            archived.list_name(),
+                            archived.completed_at().format(DATE_FORMAT)
+                        ))
+                        .weak(),
+                    );
+                    ui.separator();
+                }
+            });
+
+            if ui.button("Purge All").clicked() {
+                action = Some(ArchiveAction::PurgeAll);
+            }
+        });
+
+    action
+}
@@ -0,0 +1,342 @@
+//! This module contains the board view, which keeps a board's list windows and undo history
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDateTime;
+use eframe::egui;
+
+use crate::app::list::{ListWindow, TaskDrop};
+use crate::board::{ArchivedTask, Board, RunningTimer};
+use crate::history::History;
+use crate::id::{ListId, TaskId};
+use crate::task::{List, Reminder, Tags, Task};
+
+/// How the lists on a board are laid out
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Layout {
+    /// Each list in its own window
+    #[default]
+    Windows,
+    /// The tasks of every list in a column per status
+    Kanban,
+}
+
+impl Layout {
+    pub const ALL: [Layout; 2] = [Layout::Windows, Layout::Kanban];
+}
+
+impl std::fmt::Display for Layout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Layout::Windows => "Windows",
+            Layout::Kanban => "Kanban",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A board along with the windows displaying its lists
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+pub struct BoardView {
+    /// The board being displayed
+    board: Board,
+    /// The window displaying each list on the board
+    #[serde(default)]
+    list_windows: HashMap<ListId, ListWindow>,
+    /// How the lists are laid out
+    #[serde(default)]
+    layout: Layout,
+    /// The previous states of the board that can be returned to
+    #[serde(skip)]
+    history: History<Board>,
+    /// The state of the board when changes were last tracked
+    #[serde(skip)]
+    snapshot: Option<Board>,
+    /// The text field that had focus during the last change, used to merge keystrokes
+    #[serde(skip)]
+    editing_focus: Option<egui::Id>,
+}
+
+impl BoardView {
+    /// Creates a view of the board with a window for each of its lists
+    pub fn from_board(board: Board) -> Self {
+        let mut view = Self {
+            board,
+            list_windows: HashMap::new(),
+            layout: Layout::default(),
+            history: History::default(),
+            snapshot: None,
+            editing_focus: None,
+        };
+        view.sync_list_windows();
+        view
+    }
+
+    /// Gets a reference to the board being displayed
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Gets a mutable reference to the board being displayed. Lists added this way get a window
+    /// the next time the lists are drawn
+    pub fn board_mut(&mut self) -> &mut Board {
+        &mut self.board
+    }
+
+    /// A clone of the boards name
+    pub fn name(&self) -> String {
+        self.board.name()
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.board.set_name(name);
+    }
+
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    pub fn set_layout(&mut self, layout: Layout) {
+        self.layout = layout;
+    }
+
+    /// The window displaying the list with the given id
+    pub fn list_window(&self, id: ListId) -> Option<&ListWindow> {
+        self.list_windows.get(&id)
+    }
+
+    /// Whether the window of the list with the given id is shown
+    pub fn list_visible(&self, id: ListId) -> bool {
+        self.list_window(id)
+            .is_none_or(|list_window| list_window.visible())
+    }
+
+    pub fn list_window_mut(&mut self, id: ListId) -> Option<&mut ListWindow> {
+        self.list_windows.get_mut(&id)
+    }
+
+    pub fn list_mut(&mut self, id: ListId) -> Option<&mut List> {
+        self.board.list_by_id_mut(id)
+    }
+
+    /// Mutable references to the lists on the board along with the windows displaying them, in
+    /// the order they are on the board
+    pub fn lists_mut(&mut self) -> Vec<(&mut List, &mut ListWindow)> {
+        self.sync_list_windows();
+        let mut list_windows: HashMap<ListId, &mut ListWindow> = self
+            .list_windows
+            .iter_mut()
+            .map(|(id, list_window)| (*id, list_window))
+            .collect();
+        self.board
+            .lists_mut()
+            .iter_mut()
+            .filter_map(|list| {
+                let list_window = list_windows.remove(&list.id())?;
+                Some((list, list_window))
+            })
+            .collect()
+    }
+
+    /// The lists on the board that are shown, in the order they are on the board. Hidden lists
+    /// are left out in both layouts
+    ///
+    /// # Arguments
+    ///
+    /// * `active_tags` - The tags the lists are being filtered by
+    pub fn shown_lists(&self, active_tags: &Tags) -> Vec<ListId> {
+        self.board
+            .lists_tagged(active_tags)
+            .map(|list| list.id())
+            .filter(|list| self.list_visible(*list))
+            .collect()
+    }
+
+    /// The tasks in a list that its window's filter shows, in the order they are drawn
+    pub fn shown_tasks(&self, list: ListId, now: NaiveDateTime) -> Vec<TaskId> {
+        match (self.board.list_by_id(list), self.list_window(list)) {
+            (Some(list), Some(list_window)) => list_window.shown_tasks(list, now),
+            _ => vec![],
+        }
+    }
+
+    /// Completes a task, or reopens it if it is completed
+    ///
+    /// # Arguments
+    ///
+    /// * `list` - The list the task is in
+    /// * `task` - The task to complete or reopen
+    /// * `now`  - The current time, recorded as when the task changed
+    pub fn toggle_completed(&mut self, list: ListId, task: TaskId, now: NaiveDateTime) {
+        let Some(task) = self
+            .list_mut(list)
+            .and_then(|list| list.task_by_id_mut(task))
+        else {
+            return;
+        };
+        let previous = task.clone();
+        task.set_completed(!task.completed());
+        task.update_timestamps(&previous, now);
+    }
+
+    /// Removes a task from a list, returning it if it existed
+    pub fn delete_task(&mut self, list: ListId, task: TaskId) -> Option<Task> {
+        let list = self.list_mut(list)?;
+        let index = list.position(task)?;
+        list.remove(index)
+    }
+
+    /// Gets a mutable reference to the reminder of a task, if the task exists and has one
+    pub fn reminder_mut(&mut self, list: ListId, task: TaskId) -> Option<&mut Reminder> {
+        self.list_mut(list)?.task_by_id_mut(task)?.reminder_mut()
+    }
+
+    /// Adds a list to the end of the board along with a window to display it
+    pub fn add_list(&mut self, list: List) {
+        self.board.add_list(list);
+        self.sync_list_windows();
+    }
+
+    pub fn remove_list(&mut self, id: ListId) {
+        if let Some(index) = self.board.position(id) {
+            self.board.remove_list(index);
+        }
+        self.list_windows.remove(&id);
+    }
+
+    /// Records any change made to the board since this was last called in the undo history.
+    /// Changes made while the same text field keeps focus are merged, so that undoing reverts a
+    /// whole edit rather than a single keystroke
+    ///
+    /// # Arguments
+    ///
+    /// * `focus`         - The widget that currently has keyboard focus
+    /// * `history_depth` - How many changes can be undone
+    pub fn track_changes(&mut self, focus: Option<egui::Id>, history_depth: usize) {
+        self.history.set_depth(history_depth);
+
+        // The board is only cloned when it has changed
+        if self.snapshot.as_ref() == Some(&self.board) {
+            if focus != self.editing_focus {
+                self.editing_focus = None;
+            }
+            return;
+        }
+
+        if let Some(previous) = self.snapshot.take() {
+            if focus.is_none() || focus != self.editing_focus {
+                self.history.record(previous);
+            }
+            self.editing_focus = focus;
+        }
+        self.snapshot = Some(self.board.clone());
+    }
+
+    /// Returns the board to the state before the most recent change, if there is one
+    pub fn undo(&mut self) {
+        if let Some(previous) = self.history.undo(self.board.clone()) {
+            self.restore(previous);
+        }
+    }
+
+    /// Reapplies the most recently undone change, if there is one
+    pub fn redo(&mut self) {
+        if let Some(next) = self.history.redo(self.board.clone()) {
+            self.restore(next);
+        }
+    }
+
+    pub fn archive(&self) -> &[ArchivedTask] {
+        self.board.archive()
+    }
+
+    /// Moves the completed tasks in a list into the archive
+    ///
+    /// # Arguments
+    ///
+    /// * `list` - The list to clear the completed tasks from
+    /// * `now`  - The current time, recorded as when the tasks were archived
+    pub fn archive_completed(&mut self, list: ListId, now: NaiveDateTime) {
+        self.board.archive_completed(list, now);
+    }
+
+    /// Creates the next occurrence of every completed recurring task on the board
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time, which the next occurrences are due after
+    pub fn schedule_recurrences(&mut self, now: NaiveDateTime) {
+        for list in self.board.lists_mut() {
+            list.schedule_recurrences(now);
+        }
+    }
+
+    /// The task on the board being timed, if there is one
+    pub fn running_timer(&self) -> Option<RunningTimer> {
+        self.board.running_timer()
+    }
+
+    /// Stops the timers of every task except the running one, see [`Board::stop_timers_except`]
+    pub fn stop_timers_except(&mut self, running: &RunningTimer) {
+        self.board.stop_timers_except(running);
+    }
+
+    /// Stops the timer of the task being timed, if there is one
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time, recorded as when the timer stopped
+    pub fn stop_timer(&mut self, now: NaiveDateTime) {
+        self.board.stop_timer(now);
+    }
+
+    /// Puts the archived task at the given position back into its list
+    pub fn restore_archived(&mut self, index: usize) {
+        self.board.restore_archived(index);
+    }
+
+    /// Permanently removes the archived task at the given position
+    pub fn purge_archived(&mut self, index: usize) {
+        self.board.purge_archived(index);
+    }
+
+    /// Permanently removes every archived task
+    pub fn purge_archive(&mut self) {
+        self.board.set_archive(vec![]);
+    }
+
+    /// Moves a task that was dropped onto a list window, see [`Board::move_task`]
+    pub fn move_task(&mut self, task_drop: TaskDrop) {
+        self.board.move_task(
+            task_drop.task,
+            task_drop.from,
+            task_drop.to,
+            task_drop.index,
+        );
+    }
+
+    /// Replaces the board without recording it as a change, keeping the windows of lists that
+    /// still exist
+    pub fn restore(&mut self, board: Board) {
+        self.board = board;
+        self.sync_list_windows();
+        self.snapshot = Some(self.board.clone());
+        self.editing_focus = None;
+    }
+
+    /// Replaces the board with one loaded from elsewhere, forgetting the undo history as it
+    /// holds states from before the board was loaded
+    pub fn reload(&mut self, board: Board) {
+        self.history = History::new(self.history.depth());
+        self.restore(board);
+    }
+
+    /// Gives every list on the board a window, and forgets the windows of lists that no longer
+    /// exist
+    fn sync_list_windows(&mut self) {
+        let lists: HashSet<ListId> = self.board.lists().iter().map(|list| list.id()).collect();
+        self.list_windows.retain(|id, _| lists.contains(id));
+        for list in lists {
+            self.list_windows.entry(list).or_default();
+        }
+    }
+}
@@ -0,0 +1,211 @@
+//! This module contains the kanban layout, which shows the tasks on a board in a column per status
+use chrono::Local;
+use egui::{containers::Frame, style::Margin, RichText, Rounding, Stroke, Ui};
+
+use crate::app::list::{self, ListWindow};
+use crate::app::{search, tags, theme};
+use crate::id::ListId;
+use crate::task::{List, Status, Task};
+
+const COLUMN_OUTER_MARGIN: f32 = 5.0;
+const COLUMN_INNER_MARGIN: f32 = 10.0;
+const COLUMN_ROUNDING: f32 = 5.0;
+const COLUMN_BORDER_WIDTH: f32 = 1.0;
+
+const CARD_OUTER_MARGIN: f32 = 3.0;
+const CARD_INNER_MARGIN: f32 = 5.0;
+const CARD_ROUNDING: f32 = 5.0;
+const CARD_BORDER_WIDTH: f32 = 1.0;
+
+/// The width of the outline around the column a dragged card is over
+const DROP_OUTLINE_WIDTH: f32 = 2.0;
+
+/// Draws the tasks of every shown list as cards, in a column for each status
+///
+/// # Arguments
+///
+/// * `ui`          - The UI to draw the columns onto
+/// * `lists`       - The lists on the board and their windows, tasks are edited in place
+/// * `shown_lists` - The lists whose tasks are shown, the same lists shown as windows
+pub fn draw_kanban(
+    ui: &mut Ui,
+    lists: &mut [(&mut List, &mut ListWindow)],
+    shown_lists: &[ListId],
+) {
+    let mut dropped_on = None;
+
+    ui.columns(Status::ALL.len(), |columns| {
+        for (ui, status) in columns.iter_mut().zip(Status::ALL) {
+            if draw_column(ui, lists, shown_lists, status) {
+                dropped_on = Some(status);
+            }
+        }
+    });
+
+    // The card is moved once every column is drawn, so that it isn't drawn twice in one frame
+    if let (Some(status), Some(dragged)) = (dropped_on, list::dragged_task(ui.ctx())) {
+        let task = lists
+            .iter_mut()
+            .filter(|(list, _)| list.id() == dragged.list)
+            .find_map(|(list, _)| list.task_by_id_mut(dragged.task));
+        if let Some(task) = task {
+            let previous = task.clone();
+            task.set_status(status);
+            task.update_timestamps(&previous, Local::now().naive_local());
+        }
+    }
+}
+
+/// Draws the column of cards for the tasks with the given status
+///
+/// # Arguments
+///
+/// * `ui`          - The UI to draw the column onto
+/// * `lists`       - The lists on the board and their windows, tasks are edited in place
+/// * `shown_lists` - The lists whose tasks are shown
+/// * `status`      - The status of the tasks in the column
+///
+/// # Returns
+///
+/// Whether a dragged card was dropped onto the column
+fn draw_column(
+    ui: &mut Ui,
+    lists: &mut [(&mut List, &mut ListWindow)],
+    shown_lists: &[ListId],
+    status: Status,
+) -> bool {
+    let shown = |(list, _): &&mut (&mut List, &mut ListWindow)| shown_lists.contains(&list.id());
+    // Cards are hidden by the filter of their list's window, the same as in the windows layout
+    let now = Local::now().naive_local();
+    let in_column = |task: &Task, list_window: &ListWindow| {
+        task.status() == status && list_window.filter().matches(task, now)
+    };
+
+    let response = Frame::none()
+        .fill(theme::border(ui.ctx()))
+        .outer_margin(Margin::same(COLUMN_OUTER_MARGIN))
+        .rounding(Rounding::same(COLUMN_ROUNDING))
+        .show(ui, |ui| {
+            Frame::none()
+                .outer_margin(Margin::same(COLUMN_BORDER_WIDTH))
+                .inner_margin(Margin::same(COLUMN_INNER_MARGIN))
+                .rounding(Rounding::same(COLUMN_ROUNDING))
+                .fill(theme::background(ui.ctx()))
+                .show(ui, |ui| {
+                    let count: usize = lists
+                        .iter_mut()
+                        .filter(shown)
+                        .map(|(list, list_window)| {
+                            list.iter()
+                                .filter(|task| in_column(task, list_window))
+                                .count()
+                        })
+                        .sum();
+                    ui.heading(format!("{} ({})", status, count));
+                    ui.separator();
+
+                    egui::ScrollArea::vertical()
+                        .id_source(("kanban column", status))
+                        .auto_shrink([false, false])
+                        .show(ui, |ui| {
+                            for (list, list_window) in lists.iter_mut().filter(shown) {
+                                let list_id = list.id();
+                                let list_name = list.name();
+                                for index in 0..list.len() {
+                                    let task = list.get_mut(index).unwrap();
+                                    if in_column(task, list_window) {
+                                        *task = draw_card(ui, list_id, &list_name, task.clone());
+                                    }
+                                }
+                            }
+                        });
+                });
+        })
+        .response;
+
+    if list::dragged_task(ui.ctx()).is_none() {
+        return false;
+    }
+    let Some(pointer) = ui.ctx().pointer_interact_pos() else {
+        return false;
+    };
+    if !response.rect.contains(pointer) {
+        return false;
+    }
+
+    ui.painter().rect_stroke(
+        response.rect,
+        Rounding::same(COLUMN_ROUNDING),
+        Stroke::new(DROP_OUTLINE_WIDTH, theme::outline(ui.ctx())),
+    );
+    ui.input(|input| input.pointer.any_released())
+}
+
+/// Draws a task as a card, which can be dragged to another column or moved with its context menu
+///
+/// # Arguments
+///
+/// * `ui`        - The UI to draw the card onto
+/// * `list`      - The list the task is in
+/// * `list_name` - The name of the list the task is in, shown on the card
+/// * `task`      - The task to draw
+///
+/// # Returns
+///
+/// The task after user interaction
+fn draw_card(ui: &mut Ui, list: ListId, list_name: &str, task: Task) -> Task {
+    let previous = task.clone();
+    let mut task = task;
+    let now = Local::now().naive_local();
+
+    let response = Frame::none()
+        .fill(search::highlight_colour(ui.ctx(), task.id()).unwrap_or(theme::border(ui.ctx())))
+        .stroke(list::selected_stroke(ui, task.id()))
+        .outer_margin(Margin::same(CARD_OUTER_MARGIN))
+        .rounding(Rounding::same(CARD_ROUNDING))
+        .show(ui, |ui| {
+            Frame::none()
+                .outer_margin(Margin::same(CARD_BORDER_WIDTH))
+                .inner_margin(Margin::same(CARD_INNER_MARGIN))
+                .rounding(Rounding::same(CARD_ROUNDING))
+                .fill(theme::background(ui.ctx()))
+                .show(ui, |ui| {
+                    ui.set_width(ui.available_width());
+                    ui.horizontal(|ui| {
+                        list::draw_drag_handle(ui, list, &task);
+                        list::draw_priority_marker(ui, task.priority());
+                        let title = task.title();
+                        ui.checkbox(task.mut_completed(), title)
+                            .on_hover_text(list::task_details(&task));
+                        list::draw_timer_button(ui, &mut task, now);
+                    });
+
+                    let description = task.description();
+                    if !description.is_empty() {
+                        ui.label(description);
+                    }
+                    tags::draw_tags(ui, task.tags());
+                    ui.label(RichText::new(list_name).weak());
+                });
+        })
+        .response;
+
+    response.interact(egui::Sense::click()).context_menu(|ui| {
+        ui.label("Move to");
+        for status in Status::ALL {
+            if ui
+                .add_enabled(
+                    task.status() != status,
+                    egui::Button::new(status.to_string()),
+                )
+                .clicked()
+            {
+                task.set_status(status);
+                ui.close_menu();
+            }
+        }
+    });
+
+    task.update_timestamps(&previous, now);
+    task
+}
@@ -0,0 +1,56 @@
+//! This module contains the app state saved by versions from before boards, when the app was a
+//! single set of list windows, so that it can be moved onto a board when the app is upgraded
+use crate::app::board_view::BoardView;
+use crate::board::Board;
+use crate::task::Task;
+
+/// The name of the board the lists of older versions are moved onto
+const LEGACY_BOARD_NAME: &str = "Main";
+
+/// A list window as saved by older versions, which kept the list inside the window
+#[derive(serde::Deserialize)]
+pub struct LegacyListWindow {
+    name: String,
+    list: LegacyList,
+    #[serde(default = "visible_by_default")]
+    visible: bool,
+}
+
+/// The tasks of a list as saved by older versions
+#[derive(serde::Deserialize)]
+struct LegacyList(Vec<Task>);
+
+fn visible_by_default() -> bool {
+    true
+}
+
+/// Moves the list windows saved by an older version onto a board
+///
+/// # Arguments
+///
+/// * `list_windows` - The list windows saved by the older version
+///
+/// # Returns
+///
+/// A view of a board with a list for each window, hiding the lists whose windows were hidden
+pub fn into_board(list_windows: Vec<LegacyListWindow>) -> BoardView {
+    let mut board = Board::new(LEGACY_BOARD_NAME.to_string());
+    let mut hidden = vec![];
+    for list_window in list_windows {
+        let list = board.create_list(list_window.name);
+        for task in list_window.list.0 {
+            list.add(task);
+        }
+        if !list_window.visible {
+            hidden.push(list.id());
+        }
+    }
+
+    let mut view = BoardView::from_board(board);
+    for list in hidden {
+        if let Some(list_window) = view.list_window_mut(list) {
+            *list_window.mut_visible() = false;
+        }
+    }
+    view
+}
@@ -1,20 +1,29 @@
 //! This module contains the logic for the list windows
 
-use egui::{containers::Frame, style::Margin, Color32, Context, Rounding, Ui};
+use chrono::{Local, NaiveDateTime, NaiveTime, Timelike, Weekday};
+use egui::{
+    containers::Frame, style::Margin, Color32, Context, Rect, RichText, Rounding, Stroke, Ui,
+};
+use egui_extras::DatePickerButton;
 
-use crate::task::{List, Task};
+use crate::app::actions::{self, Action};
+use crate::app::{search, shortcuts, tags, theme};
+use crate::id::{ListId, TaskId};
+use crate::task::{
+    DueDate, DueStatus, List, Priority, Recurrence, Reminder, SortOrder, Status, Subtask, Tags,
+    Task, TaskFilter,
+};
 
 const WINDOW_WIDTH: f32 = 250.0;
 
+/// The key the task being dragged is stored under in egui's memory
+const DRAGGED_TASK_KEY: &str = "dragged task";
+/// The width of the line showing where a dragged task will be dropped
+const DROP_LINE_WIDTH: f32 = 2.0;
+
+/// The state of the window displaying a list, the list itself is kept on the board
 #[derive(Builder, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ListWindow {
-    /// The name of the list
-    name: String,
-    /// The id of the window
-    id: egui::Id,
-    #[builder(default = "List::new()")]
-    /// The list of tasks to display in the list window
-    list: List,
     #[builder(default = "String::new()")]
     /// Variable to store the contents of the add task box
     task_to_add: String,
@@ -24,6 +33,18 @@ pub struct ListWindow {
     #[builder(default = "true")]
     /// Whether to show the window or not
     visible: bool,
+    #[builder(default = "false")]
+    #[serde(default)]
+    /// Whether completed subtasks count towards the progress bar
+    count_subtasks: bool,
+    #[builder(default = "TaskFilter::All")]
+    #[serde(default)]
+    /// Which tasks are shown in the window
+    filter: TaskFilter,
+    #[builder(default = "false")]
+    #[serde(skip)]
+    /// Whether the user is being asked to confirm deleting the list
+    confirming_delete: bool,
 }
 
 impl ListWindow {
@@ -32,45 +53,99 @@ impl ListWindow {
         ListWindowBuilder::default()
     }
 
-    /// Gets a clone of the name of the ListWindow
-    pub fn name(&self) -> String {
-        self.name.clone()
-    }
-
-    /// Sets the name of the ListWindow
-    pub fn set_name(&mut self, name: String) {
-        self.name = name;
+    pub fn visible(&self) -> bool {
+        self.visible
     }
 
     /// Returns a mutable reference to the visible field
     pub fn mut_visible(&mut self) -> &mut bool {
         &mut self.visible
     }
+
+    /// Gets a reference to the filter picking which tasks are shown
+    pub fn filter(&self) -> &TaskFilter {
+        &self.filter
+    }
+
+    pub fn set_filter(&mut self, filter: TaskFilter) {
+        self.filter = filter;
+    }
+
+    /// Switches the window in and out of editing mode
+    pub fn toggle_editing(&mut self) {
+        self.editing = !self.editing;
+    }
+
+    /// The tasks in the list the window's filter shows, in the order they are drawn
+    pub fn shown_tasks(&self, list: &List, now: NaiveDateTime) -> Vec<TaskId> {
+        list.iter()
+            .filter(|task| self.filter.matches(task, now))
+            .map(|task| task.id())
+            .collect()
+    }
+}
+
+impl Default for ListWindow {
+    fn default() -> Self {
+        Self::builder().build().unwrap()
+    }
+}
+
+/// The id of the textbox for adding a task to a list, so that it can be focused by a shortcut
+pub fn add_task_id(list: ListId) -> egui::Id {
+    egui::Id::new(("add task", list))
+}
+
+/// A task being dragged by its handle
+#[derive(Clone)]
+pub(super) struct DraggedTask {
+    /// The list the task is being dragged from
+    pub(super) list: ListId,
+    pub(super) task: TaskId,
+    /// The title of the task, shown under the pointer while dragging
+    title: String,
+}
+
+/// A task that has been dragged and dropped onto a list window
+#[derive(Clone, Copy, Debug)]
+pub struct TaskDrop {
+    pub task: TaskId,
+    /// The list the task was dragged from
+    pub from: ListId,
+    /// The list the task was dropped onto
+    pub to: ListId,
+    /// The position in `to` the task was dropped before
+    pub index: usize,
 }
 
 /// Draws the list to a window
 ///
 /// # Arguments
 ///
-/// * `ctx`  - The egui handle
-/// * `list` - The list window to draw
+/// * `ctx`         - The egui handle
+/// * `list`        - The list to draw, which is edited in place
+/// * `list_window` - The state of the window displaying the list
 ///
 /// # Returns
 ///
-/// The list with any modifications that has happened, or None if the list has been deleted
-pub fn draw_list_window(ctx: &Context, list: ListWindow) -> Option<ListWindow> {
-    if !list.visible {
-        return Some(list);
+/// A task that was dropped onto the window, which the caller has to move as it may come from
+/// another list
+pub fn draw_list_window(
+    ctx: &Context,
+    list: &mut List,
+    list_window: &mut ListWindow,
+) -> Option<TaskDrop> {
+    if !list_window.visible {
+        return None;
     }
 
-    let mut list_window = list;
-    let mut delete_list = false;
-    egui::Window::new(&list_window.name)
+    let mut task_drop = None;
+    egui::Window::new(list.name())
         .resizable(false)
-        .id(list_window.id)
+        .id(egui::Id::new(list.id()))
         .show(ctx, |ui| {
             Frame::none()
-                .fill(Color32::LIGHT_GRAY)
+                .fill(theme::border(ui.ctx()))
                 .outer_margin(Margin::same(TASK_OUTER_MARGIN))
                 .rounding(Rounding::same(TASK_ROUNDING))
                 .show(ui, |ui| {
@@ -78,62 +153,208 @@ pub fn draw_list_window(ctx: &Context, list: ListWindow) -> Option<ListWindow> {
                         .outer_margin(Margin::same(TASK_BORDER_WIDTH))
                         .inner_margin(Margin::same(TASK_INNER_MARGIN))
                         .rounding(Rounding::same(TASK_ROUNDING))
-                        .fill(Color32::WHITE)
+                        .fill(theme::background(ui.ctx()))
                         .show(ui, |ui| {
                             ui.set_width(WINDOW_WIDTH);
-                            draw_progress_bar(ui, list_window.list.progress());
-                            match add_task(ui, &list_window.task_to_add) {
+                            let progress = match list_window.count_subtasks {
+                                true => list.progress_with_subtasks(),
+                                false => list.progress(),
+                            };
+                            draw_progress_bar(ui, progress);
+                            match add_task(ui, list.id(), &list_window.task_to_add) {
                                 AddTaskResult::ContinueTyping(task_to_add) => {
                                     list_window.task_to_add = task_to_add
                                 }
                                 AddTaskResult::AddTask(task_to_add) => {
-                                    if let Ok(task) = Task::builder().title(task_to_add).build() {
-                                        list_window.list.add(task);
+                                    if let Ok(task) = Task::builder()
+                                        .title(task_to_add)
+                                        .created_at(Local::now().naive_local())
+                                        .build()
+                                    {
+                                        list.add(task);
                                         list_window.task_to_add = String::new();
                                     }
                                 }
                             }
-                            ui.horizontal(|ui| {
-                                list_window.editing = draw_edit_button(ui, list_window.editing);
-                                list_window.list = draw_sort_button(ui, list_window.list.clone());
-                                list_window.list = draw_delete_completed_tasks_button(
-                                    ui,
-                                    list_window.list.clone(),
+                            let list_id = list.id();
+                            ui.horizontal_wrapped(|ui| {
+                                draw_edit_button(ui, list_id);
+                                draw_sort_button(ui, list_id);
+                                list_window.filter =
+                                    draw_filter_button(ui, list, &list_window.filter);
+                                draw_clear_completed_button(ui, list_id);
+                                list_window.confirming_delete =
+                                    draw_delete_list(ui, list_window.confirming_delete);
+                            });
+                            if list_window.confirming_delete {
+                                match draw_confirm_delete_list(ui) {
+                                    Some(true) => {
+                                        actions::dispatch(ctx, Action::DeleteList(list_id));
+                                        list_window.confirming_delete = false;
+                                    }
+                                    Some(false) => list_window.confirming_delete = false,
+                                    None => (),
+                                }
+                            }
+                            if list_window.editing {
+                                ui.checkbox(
+                                    &mut list_window.count_subtasks,
+                                    "Count subtasks in progress",
                                 );
-                                delete_list = draw_delete_list(ui);
-                            })
+                                draw_copy_markdown_button(ui, list_id);
+                            }
                         });
                 });
 
-            list_window.list = list_window
-                .list
-                .clone() // The clone is needed here due to the closure
-                .into_iter()
-                .filter_map(|task| draw_task(ui, list_window.editing, task))
-                .collect();
+            let now = Local::now().naive_local();
+            let list_id = list.id();
+            let mut task_rects = vec![];
+            let mut edits = vec![];
+            for (index, task) in list.iter().enumerate() {
+                // Filtered out tasks are kept in the list without being drawn
+                if !list_window.filter.matches(task, now) {
+                    continue;
+                }
+                let response =
+                    ui.scope(|ui| draw_task(ui, list_id, list_window.editing, now, task.clone()));
+                task_rects.push((index, response.response.rect));
+                if response.inner.as_ref() != Some(task) {
+                    edits.push((index, response.inner));
+                }
+            }
+            let hidden = list.len() - task_rects.len();
+
+            // The list is only changed when a task was, last first so that deleting a task doesn't
+            // move the ones still to be edited
+            for (index, edit) in edits.into_iter().rev() {
+                match edit {
+                    Some(edited) => {
+                        if let Some(task) = list.get_mut(index) {
+                            *task = edited;
+                        }
+                    }
+                    None => {
+                        list.remove(index);
+                    }
+                }
+            }
+
+            let label = match hidden {
+                0 => None,
+                1 => Some("1 task hidden by the filter".to_string()),
+                _ => Some(format!("{} tasks hidden by the filter", hidden)),
+            };
+            if let Some(label) = label {
+                ui.label(RichText::new(label).weak());
+            }
+
+            if let Some(dragged) = dragged_task(ctx) {
+                task_drop = draw_drop_target(ui, list_id, dragged, &task_rects, list.len());
+            }
         });
 
-    match delete_list {
-        false => Some(list_window),
-        true => None,
+    task_drop
+}
+
+/// The task currently being dragged, if there is one
+pub(super) fn dragged_task(ctx: &Context) -> Option<DraggedTask> {
+    ctx.data(|data| data.get_temp(egui::Id::new(DRAGGED_TASK_KEY)))
+}
+
+/// Draws the title of the task being dragged under the pointer, and forgets the task once the
+/// pointer is released. This should be called after all the list windows have been drawn
+///
+/// # Arguments
+///
+/// * `ctx` - The egui handle
+pub fn draw_dragged_task(ctx: &Context) {
+    let Some(dragged) = dragged_task(ctx) else {
+        return;
+    };
+
+    if !ctx.input(|input| input.pointer.any_down()) {
+        ctx.data_mut(|data| data.remove::<DraggedTask>(egui::Id::new(DRAGGED_TASK_KEY)));
+        return;
+    }
+
+    if let Some(pointer) = ctx.pointer_interact_pos() {
+        ctx.set_cursor_icon(egui::CursorIcon::Grabbing);
+        egui::Area::new(DRAGGED_TASK_KEY)
+            .order(egui::Order::Tooltip)
+            .fixed_pos(pointer)
+            .interactable(false)
+            .show(ctx, |ui| {
+                Frame::popup(ui.style()).show(ui, |ui| ui.label(dragged.title));
+            });
     }
 }
 
-/// Function to draw the edit button
+/// Draws a line where the dragged task would be dropped if the pointer is over the window
 ///
 /// # Arguments
 ///
-/// * `ui`      - The UI to draw the button onto
-/// * `editing` - The current value of editing
+/// * `ui`         - The UI of the list window
+/// * `list`       - The list the window is displaying
+/// * `dragged`    - The task being dragged
+/// * `task_rects` - The position in the list and where it was drawn of each shown task
+/// * `len`        - How many tasks are in the list, including ones that aren't shown
 ///
 /// # Returns
 ///
-/// The new value of editing
-fn draw_edit_button(ui: &mut Ui, editing: bool) -> bool {
+/// The drop if the pointer was released over the window
+fn draw_drop_target(
+    ui: &mut Ui,
+    list: ListId,
+    dragged: DraggedTask,
+    task_rects: &[(usize, Rect)],
+    len: usize,
+) -> Option<TaskDrop> {
+    let pointer = ui.ctx().pointer_interact_pos()?;
+    let window = ui.min_rect();
+    // Only the window on top can take the drop
+    if !window.contains(pointer) || ui.ctx().layer_id_at(pointer) != Some(ui.layer_id()) {
+        return None;
+    }
+
+    let (index, y) = match task_rects
+        .iter()
+        .find(|(_, rect)| rect.center().y >= pointer.y)
+    {
+        Some((index, rect)) => (*index, rect.top()),
+        None => (
+            len,
+            task_rects
+                .last()
+                .map_or(window.bottom(), |(_, rect)| rect.bottom()),
+        ),
+    };
+    ui.painter().hline(
+        window.x_range(),
+        y,
+        Stroke::new(DROP_LINE_WIDTH, theme::outline(ui.ctx())),
+    );
+
+    match ui.input(|input| input.pointer.any_released()) {
+        true => Some(TaskDrop {
+            task: dragged.task,
+            from: dragged.list,
+            to: list,
+            index,
+        }),
+        false => None,
+    }
+}
+
+/// Function to draw the edit button
+///
+/// # Arguments
+///
+/// * `ui`   - The UI to draw the button onto
+/// * `list` - The list the button switches in and out of editing mode
+fn draw_edit_button(ui: &mut Ui, list: ListId) {
     if ui.button("Edit").clicked() {
-        return !editing;
+        actions::dispatch(ui.ctx(), Action::ToggleEditing(list));
     }
-    editing
 }
 
 /// Function to draw the sort button
@@ -141,53 +362,122 @@ fn draw_edit_button(ui: &mut Ui, editing: bool) -> bool {
 /// # Arguments
 ///
 /// * `ui`   - The UI to draw the button onto
-/// * `list` - The list of tasks to sort
+/// * `list` - The list to sort by the ordering the user picks
+fn draw_sort_button(ui: &mut Ui, list: ListId) {
+    ui.menu_button("Sort", |ui| {
+        for order in SortOrder::ALL {
+            if ui.button(order.to_string()).clicked() {
+                actions::dispatch(ui.ctx(), Action::SortList(list, order));
+                ui.close_menu();
+            }
+        }
+    });
+}
+
+/// Function to draw the button for picking which tasks are shown
+///
+/// # Arguments
+///
+/// * `ui`     - The UI to draw the button onto
+/// * `list`   - The list being filtered, whose task tags can be filtered by
+/// * `filter` - The current filter
 ///
 /// # Returns
 ///
-/// `list` sorted with completed tasks at the top if the button is clicked, otherwise the original
-/// List
-fn draw_sort_button(ui: &mut Ui, list: List) -> List {
-    if ui.button("Sort").clicked() {
-        return list
-            .clone()
-            .into_iter()
-            .filter(|task| task.completed())
-            .chain(list.into_iter().filter(|task| !task.completed()))
-            .collect();
+/// The filter after user interaction
+fn draw_filter_button(ui: &mut Ui, list: &List, filter: &TaskFilter) -> TaskFilter {
+    let mut filter = filter.clone();
+    let mut tags = Tags::new();
+    for task in list.iter() {
+        tags.extend(task.tags().iter().cloned());
     }
-    list
+
+    ui.menu_button(format!("Show: {}", filter), |ui| {
+        let mut options = vec![
+            TaskFilter::All,
+            TaskFilter::Open,
+            TaskFilter::Completed,
+            TaskFilter::DueSoon,
+        ];
+        options.extend(tags.iter().map(|tag| TaskFilter::Tagged(tag.clone())));
+        for option in options {
+            let label = option.to_string();
+            if ui.selectable_value(&mut filter, option, label).clicked() {
+                ui.close_menu();
+            }
+        }
+    });
+    filter
 }
 
-/// Function to draw the delete completed tasks button
+/// Function to draw the menu for clearing the completed tasks from a list, either into the
+/// archive or permanently
 ///
 /// # Arguments
 ///
-/// * `ui`   - The UI to draw the button onto
-/// * `list` - The list of tasks to filter
+/// * `ui`   - The UI to draw the menu onto
+/// * `list` - The list to clear the completed tasks from
+fn draw_clear_completed_button(ui: &mut Ui, list: ListId) {
+    ui.menu_button("Clear Completed", |ui| {
+        if ui.button("Move to Archive").clicked() {
+            actions::dispatch(ui.ctx(), Action::ArchiveCompleted(list));
+            ui.close_menu();
+        }
+        if ui.button("Delete").clicked() {
+            actions::dispatch(ui.ctx(), Action::DeleteCompleted(list));
+            ui.close_menu();
+        }
+    });
+}
+
+/// Function to draw the button that copies the list to the clipboard as markdown
 ///
-/// # Returns
+/// # Arguments
 ///
-/// `list` sorted with completed tasks deleted if the button is clicked, otherwise the original
-/// List
-fn draw_delete_completed_tasks_button(ui: &mut Ui, list: List) -> List {
-    if ui.button("Delete Completed").clicked() {
-        return list.into_iter().filter(|task| !task.completed()).collect();
+/// * `ui`   - The UI to draw the button onto
+/// * `list` - The list to copy
+fn draw_copy_markdown_button(ui: &mut Ui, list: ListId) {
+    if ui.button("Copy as Markdown").clicked() {
+        actions::dispatch(ui.ctx(), Action::CopyList(list));
     }
-    list
 }
 
 /// Function to draw the delete list button
 ///
 /// # Arguments
 ///
-/// * `ui` - The UI to draw the button onto
+/// * `ui`                - The UI to draw the button onto
+/// * `confirming_delete` - Whether the user is already being asked to confirm deleting the list
 ///
 /// # Returns
 ///
-/// Whether the button was clicked or not
-fn draw_delete_list(ui: &mut Ui) -> bool {
-    ui.button("Delete").clicked()
+/// Whether the user should be asked to confirm deleting the list
+fn draw_delete_list(ui: &mut Ui, confirming_delete: bool) -> bool {
+    ui.button("Delete").clicked() || confirming_delete
+}
+
+/// Function to draw the prompt confirming the list should be deleted
+///
+/// # Arguments
+///
+/// * `ui` - The UI to draw the prompt onto
+///
+/// # Returns
+///
+/// Some(true) if the list should be deleted, Some(false) if the user cancelled, or None if the
+/// user hasn't answered yet
+fn draw_confirm_delete_list(ui: &mut Ui) -> Option<bool> {
+    let mut answer = None;
+    ui.horizontal(|ui| {
+        ui.label("Delete this list?");
+        if ui.button("Yes").clicked() {
+            answer = Some(true);
+        }
+        if ui.button("No").clicked() {
+            answer = Some(false);
+        }
+    });
+    answer
 }
 
 /// Function to draw the progress bar
@@ -218,17 +508,17 @@ enum AddTaskResult {
 /// # Returns
 ///
 /// An AddTaskResult containg the end contents of the textbox
-fn add_task(ui: &mut Ui, task_name: &str) -> AddTaskResult {
+fn add_task(ui: &mut Ui, list: ListId, task_name: &str) -> AddTaskResult {
     let mut task_name = task_name.to_string();
     let mut add_task = false;
     Frame::none()
-        .fill(Color32::LIGHT_GRAY)
+        .fill(theme::border(ui.ctx()))
         .outer_margin(Margin::symmetric(0.0, TASK_OUTER_MARGIN))
         .inner_margin(Margin::same(TASK_BORDER_WIDTH))
         .rounding(Rounding::same(TASK_ROUNDING - 2.0))
         .show(ui, |ui| {
             if ui
-                .text_edit_singleline(&mut task_name)
+                .add(egui::TextEdit::singleline(&mut task_name).id(add_task_id(list)))
                 .on_hover_text("Add a new task")
                 .lost_focus()
             {
@@ -256,18 +546,33 @@ const TASK_BORDER_WIDTH: f32 = 1.0;
 /// # Arguments
 ///
 /// * `ui`      - The UI to draw the task UI widget onto
+/// * `list`    - The list the task is in
 /// * `editing` - Whether the list window is in editing mode
+/// * `now`     - The current time, used to flag overdue tasks
 /// * `task`    - The task for the widget to display
 ///
 /// # Returns
 ///
 /// An option containg either the task with modifications, or None if the task has been deleted
-fn draw_task(ui: &mut Ui, editing: bool, task: Task) -> Option<Task> {
+fn draw_task(
+    ui: &mut Ui,
+    list: ListId,
+    editing: bool,
+    now: NaiveDateTime,
+    task: Task,
+) -> Option<Task> {
+    let previous = task.clone();
     let mut task = task;
     let mut delete_task = false;
+    let due_status = task.due_status(now);
 
     Frame::none()
-        .fill(Color32::LIGHT_GRAY)
+        .fill(
+            search::highlight_colour(ui.ctx(), task.id())
+                .or(due_status_colour(due_status))
+                .unwrap_or(theme::border(ui.ctx())),
+        )
+        .stroke(selected_stroke(ui, task.id()))
         .outer_margin(Margin::same(TASK_OUTER_MARGIN))
         .rounding(Rounding::same(TASK_ROUNDING))
         .show(ui, |ui| {
@@ -275,46 +580,266 @@ fn draw_task(ui: &mut Ui, editing: bool, task: Task) -> Option<Task> {
                 .outer_margin(Margin::same(TASK_BORDER_WIDTH))
                 .inner_margin(Margin::same(TASK_INNER_MARGIN))
                 .rounding(Rounding::same(TASK_ROUNDING))
-                .fill(Color32::WHITE)
+                .fill(theme::background(ui.ctx()))
                 .show(ui, |ui| {
                     ui.set_width(WINDOW_WIDTH);
 
                     if editing {
                         ui.horizontal(|ui| {
-                            // Having a border frame here so that the button lines up with the text
-                            // edit field
-                            Frame::none()
-                                .fill(Color32::LIGHT_GRAY)
-                                .outer_margin(Margin::symmetric(0.0, TASK_OUTER_MARGIN))
-                                .inner_margin(Margin::same(TASK_BORDER_WIDTH))
-                                .rounding(Rounding::same(TASK_ROUNDING - 2.0))
-                                .show(ui, |ui| {
-                                    if ui.button("X").clicked() {
-                                        delete_task = true;
-                                    }
-                                });
+                            delete_task = delete_button(ui);
                             task.set_title(textfield(ui, task.title()));
                         });
 
                         task.set_description(textfield(ui, task.description()));
+                        *task.tags_mut() = tags::draw_tags_editor(
+                            ui,
+                            egui::Id::new(("task tags", task.id())),
+                            task.tags().clone(),
+                        );
+                        draw_subtasks_editor(ui, &mut task);
+                        task.set_priority(draw_priority_editor(ui, task.id(), task.priority()));
+                        task.set_status(draw_status_editor(ui, task.id(), task.status()));
+                        task.set_due(draw_due_date_editor(ui, task.id(), task.due()));
+                        task.set_recurrence(draw_recurrence_editor(
+                            ui,
+                            task.id(),
+                            task.recurrence().cloned(),
+                        ));
+                        task.set_reminder(draw_reminder_editor(ui, task.id(), task.reminder()));
                     } else {
-                        let title = task.title();
-                        ui.checkbox(task.mut_completed(), title);
+                        ui.horizontal(|ui| {
+                            draw_drag_handle(ui, list, &task);
+                            draw_priority_marker(ui, task.priority());
+                            let title = task.title();
+                            ui.checkbox(task.mut_completed(), title)
+                                .on_hover_text(task_details(&task));
+                            draw_timer_button(ui, &mut task, now);
+                            if task.status() == Status::Doing {
+                                ui.label(RichText::new(Status::Doing.to_string()).weak());
+                            }
+                        });
 
                         let description = task.description();
                         if !description.is_empty() {
                             ui.label(description);
                         }
+
+                        tags::draw_tags(ui, task.tags());
+                        draw_subtasks(ui, &mut task);
+
+                        if let Some(due) = task.due() {
+                            let label = RichText::new(format!("Due {}", due));
+                            match due_status_colour(due_status) {
+                                Some(colour) => ui.label(label.color(colour).strong()),
+                                None => ui.label(label),
+                            };
+                        }
+                        if let Some(recurrence) = task.recurrence() {
+                            ui.label(RichText::new(format!("↻ {}", recurrence)).weak());
+                        }
+                        if !task.time_log().is_empty() {
+                            let spent = format_duration(task.time_spent(now));
+                            ui.label(RichText::new(format!("⏱ {}", spent)).weak());
+                        }
+                        if let Some(reminder) = task.reminder().filter(|r| !r.dismissed()) {
+                            let at = reminder.goes_off_at().format(TIMESTAMP_FORMAT);
+                            ui.label(RichText::new(format!("⏰ {}", at)).weak());
+                        }
                     }
                 });
         });
 
+    task.update_timestamps(&previous, now);
     match delete_task {
         false => Some(task),
         true => None,
     }
 }
 
+/// The format timestamps are shown in
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Describes when the task was created, completed and last modified, for the task's tooltip
+///
+/// # Arguments
+///
+/// * `task` - The task to describe
+///
+/// # Returns
+///
+/// A line for each timestamp the task has
+pub(super) fn task_details(task: &Task) -> String {
+    let mut lines = vec![];
+    let timestamps = [
+        ("Created", task.created_at()),
+        ("Completed", task.completed_at()),
+        ("Modified", task.modified_at()),
+    ];
+    for (label, timestamp) in timestamps {
+        if let Some(timestamp) = timestamp {
+            lines.push(format!("{}: {}", label, timestamp.format(TIMESTAMP_FORMAT)));
+        }
+    }
+    if let Some(duration) = task.time_to_complete() {
+        lines.push(format!("Took {}", format_duration(duration)));
+    }
+    if let Some(session) = task.time_log().last() {
+        // Counting up to when the last session stopped leaves out a timer that is still running
+        let spent = task.time_spent(session.stopped_at().unwrap_or(session.started_at()));
+        lines.push(format!(
+            "Timed {} over {} sessions",
+            format_duration(spent),
+            task.time_log().len()
+        ));
+    }
+
+    match lines.is_empty() {
+        true => "No history recorded for this task".to_string(),
+        false => lines.join("\n"),
+    }
+}
+
+/// Formats a duration in the largest units that keep it readable, such as "2d 3h" or "45m"
+pub(super) fn format_duration(duration: chrono::Duration) -> String {
+    let minutes = duration.num_minutes().max(0);
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    match (days, hours) {
+        (0, 0) => format!("{}m", minutes),
+        (0, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h", days, hours),
+    }
+}
+
+/// The width of the outline around the task selected for keyboard shortcuts
+const SELECTED_STROKE_WIDTH: f32 = 2.0;
+
+/// The outline drawn around a task, which is only visible if it is selected for keyboard shortcuts
+pub(super) fn selected_stroke(ui: &Ui, task: TaskId) -> Stroke {
+    match shortcuts::is_selected(ui.ctx(), task) {
+        true => Stroke::new(SELECTED_STROKE_WIDTH, ui.visuals().selection.bg_fill),
+        false => Stroke::NONE,
+    }
+}
+
+/// Draws a button to start or stop timing a task. Completed tasks can't be timed, so it is only
+/// drawn for open tasks
+///
+/// # Arguments
+///
+/// * `ui`   - The UI to draw the button onto
+/// * `task` - The task the button times
+/// * `now`  - The current time, recorded as when the timer started or stopped
+pub(super) fn draw_timer_button(ui: &mut Ui, task: &mut Task, now: NaiveDateTime) {
+    if task.completed() {
+        return;
+    }
+
+    match task.timer_started_at() {
+        Some(_) => {
+            if ui.small_button("⏹").on_hover_text("Stop timing").clicked() {
+                task.stop_timer(now);
+            }
+        }
+        None => {
+            if ui.small_button("▶").on_hover_text("Start timing").clicked() {
+                task.start_timer(now);
+            }
+        }
+    }
+}
+
+/// Draws a handle that the task can be dragged by, to reorder it or move it to another list
+///
+/// # Arguments
+///
+/// * `ui`   - The UI to draw the handle onto
+/// * `list` - The list the task is in
+/// * `task` - The task the handle drags
+pub(super) fn draw_drag_handle(ui: &mut Ui, list: ListId, task: &Task) {
+    let handle = ui
+        .add(egui::Label::new("☰").sense(egui::Sense::drag()))
+        .on_hover_cursor(egui::CursorIcon::Grab);
+    if handle.drag_started() {
+        let dragged = DraggedTask {
+            list,
+            task: task.id(),
+            title: task.title(),
+        };
+        ui.data_mut(|data| data.insert_temp(egui::Id::new(DRAGGED_TASK_KEY), dragged));
+    }
+}
+
+/// Draws a delete button with a frame border, so that it lines up with text edit fields
+///
+/// # Arguments
+///
+/// * `ui` - The UI to draw the button on
+///
+/// # Returns
+///
+/// Whether the button was clicked or not
+fn delete_button(ui: &mut Ui) -> bool {
+    Frame::none()
+        .fill(theme::border(ui.ctx()))
+        .outer_margin(Margin::symmetric(0.0, TASK_OUTER_MARGIN))
+        .inner_margin(Margin::same(TASK_BORDER_WIDTH))
+        .rounding(Rounding::same(TASK_ROUNDING - 2.0))
+        .show(ui, |ui| ui.button("X").clicked())
+        .inner
+}
+
+/// Draws the checklist of a task's subtasks indented beneath the task
+///
+/// # Arguments
+///
+/// * `ui`   - The UI to draw the checklist on
+/// * `task` - The task whose subtasks to draw
+fn draw_subtasks(ui: &mut Ui, task: &mut Task) {
+    if task.subtasks().is_empty() {
+        return;
+    }
+
+    ui.indent(("subtasks", task.id()), |ui| {
+        for index in 0..task.subtasks().len() {
+            if let Some(subtask) = task.subtask_mut(index) {
+                let title = subtask.title();
+                ui.checkbox(subtask.mut_completed(), title);
+            }
+        }
+    });
+}
+
+/// Draws the controls for editing, adding and removing a task's subtasks
+///
+/// # Arguments
+///
+/// * `ui`   - The UI to draw the controls on
+/// * `task` - The task whose subtasks to edit
+fn draw_subtasks_editor(ui: &mut Ui, task: &mut Task) {
+    let mut subtask_to_delete = None;
+
+    ui.indent(("subtasks", task.id()), |ui| {
+        for index in 0..task.subtasks().len() {
+            ui.horizontal(|ui| {
+                if delete_button(ui) {
+                    subtask_to_delete = Some(index);
+                }
+                if let Some(subtask) = task.subtask_mut(index) {
+                    subtask.set_title(textfield(ui, subtask.title()));
+                }
+            });
+        }
+
+        if ui.button("Add Subtask").clicked() {
+            task.add_subtask(Subtask::new(String::new()));
+        }
+    });
+
+    if let Some(index) = subtask_to_delete {
+        task.remove_subtask(index);
+    }
+}
+
 /// Draws a singleline text edit to the UI with a frame border
 ///
 /// # Arguments
@@ -328,10 +853,300 @@ fn draw_task(ui: &mut Ui, editing: bool, task: Task) -> Option<Task> {
 fn textfield(ui: &mut Ui, contents: String) -> String {
     let mut contents = contents;
     Frame::none()
-        .fill(Color32::LIGHT_GRAY)
+        .fill(theme::border(ui.ctx()))
         .outer_margin(Margin::symmetric(0.0, TASK_OUTER_MARGIN))
         .inner_margin(Margin::same(TASK_BORDER_WIDTH))
         .rounding(Rounding::same(TASK_ROUNDING - 2.0))
         .show(ui, |ui| ui.text_edit_singleline(&mut contents));
     contents
 }
+
+/// The colour to flag a task with given its due status
+///
+/// # Arguments
+///
+/// * `status` - The due status of the task
+///
+/// # Returns
+///
+/// The colour to flag the task with, or None if the task doesn't need flagging
+fn due_status_colour(status: Option<DueStatus>) -> Option<Color32> {
+    match status {
+        Some(DueStatus::Overdue) => Some(Color32::from_rgb(220, 50, 50)),
+        Some(DueStatus::DueToday) => Some(Color32::from_rgb(230, 140, 0)),
+        Some(DueStatus::Upcoming) | None => None,
+    }
+}
+
+/// The colour of the marker for a priority
+///
+/// # Arguments
+///
+/// * `priority` - The priority to get the colour of
+///
+/// # Returns
+///
+/// The colour of the marker, or None if the priority has no marker
+fn priority_colour(priority: Priority) -> Option<Color32> {
+    match priority {
+        Priority::None => None,
+        Priority::Low => Some(Color32::from_rgb(80, 160, 80)),
+        Priority::Medium => Some(Color32::from_rgb(70, 130, 210)),
+        Priority::High => Some(Color32::from_rgb(230, 140, 0)),
+        Priority::Urgent => Some(Color32::from_rgb(220, 50, 50)),
+    }
+}
+
+/// The radius of the priority marker
+const PRIORITY_MARKER_RADIUS: f32 = 4.0;
+
+/// Draws a coloured dot marking the priority of a task
+///
+/// # Arguments
+///
+/// * `ui`       - The UI to draw the marker on
+/// * `priority` - The priority of the task
+pub(super) fn draw_priority_marker(ui: &mut Ui, priority: Priority) {
+    let Some(colour) = priority_colour(priority) else {
+        return;
+    };
+    let (rect, response) = ui.allocate_exact_size(
+        egui::Vec2::splat(PRIORITY_MARKER_RADIUS * 2.0),
+        egui::Sense::hover(),
+    );
+    ui.painter()
+        .circle_filled(rect.center(), PRIORITY_MARKER_RADIUS, colour);
+    response.on_hover_text(format!("{} priority", priority));
+}
+
+/// Draws the combo box for editing the priority of a task
+///
+/// # Arguments
+///
+/// * `ui`       - The UI to draw the combo box on
+/// * `task_id`  - The id of the task being edited, used to keep the combo box unique
+/// * `priority` - The current priority of the task
+///
+/// # Returns
+///
+/// The priority after user interaction
+fn draw_priority_editor(ui: &mut Ui, task_id: TaskId, priority: Priority) -> Priority {
+    let mut priority = priority;
+    egui::ComboBox::from_id_source(("priority", task_id))
+        .selected_text(format!("Priority: {}", priority))
+        .show_ui(ui, |ui| {
+            for option in Priority::ALL {
+                ui.selectable_value(&mut priority, option, option.to_string());
+            }
+        });
+    priority
+}
+
+/// Draws the combo box for editing the status of a task
+///
+/// # Arguments
+///
+/// * `ui`      - The UI to draw the combo box on
+/// * `task_id` - The id of the task being edited, used to keep the combo box unique
+/// * `status`  - The current status of the task
+///
+/// # Returns
+///
+/// The status after user interaction
+fn draw_status_editor(ui: &mut Ui, task_id: TaskId, status: Status) -> Status {
+    let mut status = status;
+    egui::ComboBox::from_id_source(("status", task_id))
+        .selected_text(format!("Status: {}", status))
+        .show_ui(ui, |ui| {
+            for option in Status::ALL {
+                ui.selectable_value(&mut status, option, option.to_string());
+            }
+        });
+    status
+}
+
+/// Draws the controls for editing the due date of a task
+///
+/// # Arguments
+///
+/// * `ui`      - The UI to draw the controls on
+/// * `task_id` - The id of the task being edited, used to keep the date picker unique
+/// * `due`     - The current due date of the task
+///
+/// # Returns
+///
+/// The due date after user interaction
+fn draw_due_date_editor(ui: &mut Ui, task_id: TaskId, due: Option<DueDate>) -> Option<DueDate> {
+    let mut has_due = due.is_some();
+    let mut date = due.map_or_else(|| Local::now().date_naive(), |due| due.date());
+    let mut has_time = due.and_then(|due| due.time()).is_some();
+    let time = due
+        .and_then(|due| due.time())
+        .unwrap_or(NaiveTime::MIN + chrono::Duration::hours(9));
+    let mut hour = time.hour();
+    let mut minute = time.minute();
+
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut has_due, "Due");
+        if has_due {
+            let id_source = task_id.to_string();
+            ui.add(DatePickerButton::new(&mut date).id_source(&id_source));
+        }
+    });
+
+    if !has_due {
+        return None;
+    }
+
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut has_time, "At");
+        if has_time {
+            ui.add(egui::DragValue::new(&mut hour).clamp_range(0..=23));
+            ui.label(":");
+            ui.add(egui::DragValue::new(&mut minute).clamp_range(0..=59));
+        }
+    });
+
+    let time = match has_time {
+        true => NaiveTime::from_hms_opt(hour, minute, 0),
+        false => None,
+    };
+    Some(DueDate::new(date, time))
+}
+
+/// The days of the week in the order they are offered when picking which days a task repeats on
+const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Draws the controls for editing how often a task repeats
+///
+/// # Arguments
+///
+/// * `ui`         - The UI to draw the controls on
+/// * `task_id`    - The id of the task being edited, used to keep the combo box unique
+/// * `recurrence` - How often the task currently repeats
+///
+/// # Returns
+///
+/// The recurrence after user interaction
+fn draw_recurrence_editor(
+    ui: &mut Ui,
+    task_id: TaskId,
+    recurrence: Option<Recurrence>,
+) -> Option<Recurrence> {
+    let mut recurrence = recurrence;
+    let options = [
+        None,
+        Some(Recurrence::Daily),
+        Some(Recurrence::Weekly(vec![])),
+        Some(Recurrence::Monthly),
+        Some(Recurrence::EveryDays(2)),
+    ];
+    let label = |option: &Option<Recurrence>| match option {
+        None => "Doesn't repeat".to_string(),
+        Some(recurrence) => recurrence.to_string(),
+    };
+
+    egui::ComboBox::from_id_source(("recurrence", task_id))
+        .selected_text(label(&recurrence))
+        .show_ui(ui, |ui| {
+            for option in options {
+                // Only the kind of recurrence is compared, so picking it again keeps its details
+                let kind = |recurrence: &Option<Recurrence>| match recurrence {
+                    Some(Recurrence::MonthlyOn(_)) => {
+                        Some(std::mem::discriminant(&Recurrence::Monthly))
+                    }
+                    recurrence => recurrence.as_ref().map(std::mem::discriminant),
+                };
+                let selected = kind(&option) == kind(&recurrence);
+                if ui.selectable_label(selected, label(&option)).clicked() && !selected {
+                    recurrence = option;
+                }
+            }
+        });
+
+    match recurrence.as_mut() {
+        Some(Recurrence::Weekly(days)) => {
+            ui.horizontal_wrapped(|ui| {
+                for day in WEEKDAYS {
+                    let mut on = days.contains(&day);
+                    if ui.toggle_value(&mut on, day.to_string()).changed() {
+                        match on {
+                            true => days.push(day),
+                            false => days.retain(|existing| *existing != day),
+                        }
+                        days.sort_by_key(|day| day.num_days_from_monday());
+                    }
+                }
+            });
+        }
+        Some(Recurrence::EveryDays(days)) => {
+            ui.horizontal(|ui| {
+                ui.label("Every");
+                ui.add(egui::DragValue::new(days).clamp_range(1..=365));
+                ui.label("days");
+            });
+        }
+        _ => (),
+    }
+    recurrence
+}
+
+/// Draws the controls for editing when the user is reminded about a task. Changing the time of
+/// the reminder sets it going off again, even if it was snoozed or dismissed
+///
+/// # Arguments
+///
+/// * `ui`       - The UI to draw the controls on
+/// * `task_id`  - The id of the task being edited, used to keep the date picker unique
+/// * `reminder` - The current reminder of the task
+///
+/// # Returns
+///
+/// The reminder after user interaction
+fn draw_reminder_editor(
+    ui: &mut Ui,
+    task_id: TaskId,
+    reminder: Option<Reminder>,
+) -> Option<Reminder> {
+    let mut has_reminder = reminder.is_some();
+    let at = reminder.map_or_else(
+        || {
+            Local::now()
+                .date_naive()
+                .and_time(NaiveTime::MIN + chrono::Duration::hours(9))
+        },
+        |reminder| reminder.at(),
+    );
+    let mut date = at.date();
+    let mut hour = at.hour();
+    let mut minute = at.minute();
+
+    ui.horizontal(|ui| {
+        ui.checkbox(&mut has_reminder, "Remind");
+        if has_reminder {
+            let id_source = format!("reminder {}", task_id);
+            ui.add(DatePickerButton::new(&mut date).id_source(&id_source));
+            ui.add(egui::DragValue::new(&mut hour).clamp_range(0..=23));
+            ui.label(":");
+            ui.add(egui::DragValue::new(&mut minute).clamp_range(0..=59));
+        }
+    });
+
+    if !has_reminder {
+        return None;
+    }
+
+    let at = NaiveTime::from_hms_opt(hour, minute, 0).map_or(at, |time| date.and_time(time));
+    match reminder {
+        Some(reminder) if reminder.at() == at => Some(reminder),
+        _ => Some(Reminder::new(at)),
+    }
+}
@@ -0,0 +1,123 @@
+//! This module contains the command palette, for finding and running any action by name
+use egui::{Align2, Context, Key, Vec2};
+
+use crate::app::actions::{Action, Command};
+use crate::search::{self, SearchOptions};
+
+const PALETTE_WIDTH: f32 = 400.0;
+/// How far below the top of the screen the palette is shown
+const PALETTE_OFFSET: f32 = 60.0;
+/// The most commands listed at once
+const MAX_RESULTS: usize = 12;
+
+/// The state of the command palette while it is open
+#[derive(Default)]
+pub struct Palette {
+    open: bool,
+    /// What the user has typed to find a command
+    query: String,
+    /// The position in the results of the command that enter runs
+    selected: usize,
+}
+
+impl Palette {
+    /// Opens the palette with an empty query
+    pub fn open(&mut self) {
+        *self = Self {
+            open: true,
+            ..Self::default()
+        };
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.open
+    }
+
+    /// Draws the palette if it is open. The arrow keys pick a command, enter runs it and escape
+    /// closes the palette
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx`      - The egui handle
+    /// * `commands` - The commands that can be run
+    ///
+    /// # Returns
+    ///
+    /// The action of the command the user ran, if any
+    pub fn draw(&mut self, ctx: &Context, commands: &[Command]) -> Option<Action> {
+        if !self.open {
+            return None;
+        }
+
+        let options = SearchOptions {
+            case_sensitive: false,
+            fuzzy: true,
+        };
+        let mut results: Vec<(usize, &Command)> = commands
+            .iter()
+            .filter_map(|command| {
+                search::score(self.query.trim(), &command.label, options)
+                    .map(|score| (score, command))
+            })
+            .collect();
+        results.sort_by_key(|(score, _)| *score);
+        results.truncate(MAX_RESULTS);
+
+        let (up, down, enter, escape) = ctx.input(|input| {
+            (
+                input.key_pressed(Key::ArrowUp),
+                input.key_pressed(Key::ArrowDown),
+                input.key_pressed(Key::Enter),
+                input.key_pressed(Key::Escape),
+            )
+        });
+        if down {
+            self.selected += 1;
+        }
+        if up {
+            self.selected = self.selected.saturating_sub(1);
+        }
+        self.selected = self.selected.min(results.len().saturating_sub(1));
+
+        let mut ran = None;
+        if enter {
+            ran = results
+                .get(self.selected)
+                .map(|(_, command)| command.action.clone());
+        }
+
+        egui::Window::new("Command Palette")
+            .title_bar(false)
+            .resizable(false)
+            .anchor(Align2::CENTER_TOP, Vec2::new(0.0, PALETTE_OFFSET))
+            .show(ctx, |ui| {
+                ui.set_width(PALETTE_WIDTH);
+                let query = ui.add(
+                    egui::TextEdit::singleline(&mut self.query)
+                        .hint_text("Type a command")
+                        .desired_width(PALETTE_WIDTH),
+                );
+                if query.changed() {
+                    self.selected = 0;
+                }
+                query.request_focus();
+
+                if results.is_empty() {
+                    ui.label("No matching commands");
+                }
+                for (index, (_, command)) in results.iter().enumerate() {
+                    if ui
+                        .selectable_label(index == self.selected, &command.label)
+                        .clicked()
+                    {
+                        ran = Some(command.action.clone());
+                    }
+                }
+            });
+
+        if ran.is_some() || escape {
+            self.open = false;
+        }
+        ran
+    }
+}
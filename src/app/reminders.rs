@@ -0,0 +1,85 @@
+//! This module contains the notification toasts shown when a task's reminder goes off
+use chrono::Duration;
+use egui::{containers::Frame, style::Margin, Align2, Context, Rounding, Vec2};
+
+use crate::app::theme;
+use crate::reminders::DueReminder;
+
+/// The width of each toast
+const TOAST_WIDTH: f32 = 250.0;
+/// How far the toasts are kept from the corner of the screen
+const TOAST_OFFSET: f32 = 10.0;
+/// The inner margin of each toast
+const TOAST_INNER_MARGIN: f32 = 10.0;
+/// How rounded the corners of each toast are
+const TOAST_ROUNDING: f32 = 5.0;
+/// The border width of each toast
+const TOAST_BORDER_WIDTH: f32 = 1.0;
+
+/// The snooze lengths offered on each toast
+const SNOOZE_OPTIONS: [(&str, i64); 2] = [("10 min", 10), ("1 hour", 60)];
+
+/// Enum to model the actions the user can take on a reminder toast
+pub enum ReminderAction {
+    /// Put the reminder off for the given length of time
+    Snooze(DueReminder, Duration),
+    /// Stop the reminder from going off
+    Dismiss(DueReminder),
+}
+
+/// Draws a toast in the bottom right corner of the screen for each reminder that has gone off
+///
+/// # Arguments
+///
+/// * `ctx`       - The egui handle
+/// * `reminders` - The reminders that have gone off, with the name of the board they are on
+///
+/// # Returns
+///
+/// The action the user took, if any
+pub fn draw_reminders(
+    ctx: &Context,
+    reminders: &[(String, DueReminder)],
+) -> Option<ReminderAction> {
+    if reminders.is_empty() {
+        return None;
+    }
+
+    let mut action = None;
+    egui::Area::new("reminders")
+        .anchor(Align2::RIGHT_BOTTOM, Vec2::splat(-TOAST_OFFSET))
+        .show(ctx, |ui| {
+            for (board_name, reminder) in reminders {
+                Frame::none()
+                    .fill(theme::border(ui.ctx()))
+                    .outer_margin(Margin::symmetric(0.0, TOAST_OFFSET / 2.0))
+                    .rounding(Rounding::same(TOAST_ROUNDING))
+                    .show(ui, |ui| {
+                        Frame::none()
+                            .outer_margin(Margin::same(TOAST_BORDER_WIDTH))
+                            .inner_margin(Margin::same(TOAST_INNER_MARGIN))
+                            .rounding(Rounding::same(TOAST_ROUNDING))
+                            .fill(theme::background(ui.ctx()))
+                            .show(ui, |ui| {
+                                ui.set_width(TOAST_WIDTH);
+                                ui.strong(format!("⏰ {}", reminder.title));
+                                ui.label(egui::RichText::new(board_name).weak());
+                                ui.horizontal(|ui| {
+                                    for (label, minutes) in SNOOZE_OPTIONS {
+                                        if ui.button(format!("Snooze {}", label)).clicked() {
+                                            action = Some(ReminderAction::Snooze(
+                                                reminder.clone(),
+                                                Duration::minutes(minutes),
+                                            ));
+                                        }
+                                    }
+                                    if ui.button("Dismiss").clicked() {
+                                        action = Some(ReminderAction::Dismiss(reminder.clone()));
+                                    }
+                                });
+                            });
+                    });
+            }
+        });
+    action
+}
@@ -0,0 +1,70 @@
+//! This module contains the search results window and the highlighting of the task jumped to
+use egui::{Color32, Context};
+
+use crate::id::TaskId;
+use crate::search::SearchMatch;
+
+/// The key the highlighted task is stored under in egui's memory
+const HIGHLIGHT_KEY: &str = "highlighted task";
+/// How long a task stays highlighted after jumping to it, in seconds
+const HIGHLIGHT_DURATION: f64 = 2.0;
+/// The colour of the border around a highlighted task
+const HIGHLIGHT_COLOUR: Color32 = Color32::from_rgb(66, 133, 244);
+
+/// Draws the window listing the tasks that matched the search
+///
+/// # Arguments
+///
+/// * `ctx`     - The egui handle
+/// * `matches` - The tasks that matched the search
+///
+/// # Returns
+///
+/// The match the user clicked on, if any
+pub fn draw_search_results(ctx: &Context, matches: &[SearchMatch]) -> Option<SearchMatch> {
+    let mut clicked = None;
+
+    egui::Window::new("Search Results")
+        .resizable(false)
+        .show(ctx, |ui| {
+            if matches.is_empty() {
+                ui.label("No tasks match");
+                return;
+            }
+
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for found in matches {
+                    let text = format!("{} › {}", found.list_name, found.title);
+                    if ui
+                        .selectable_label(false, text)
+                        .on_hover_text("Jump to the task")
+                        .clicked()
+                    {
+                        clicked = Some(found.clone());
+                    }
+                }
+            });
+        });
+
+    clicked
+}
+
+/// Highlights a task for a short while, so that it can be spotted after jumping to it
+pub fn highlight(ctx: &Context, task: TaskId) {
+    let until = ctx.input(|input| input.time) + HIGHLIGHT_DURATION;
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new(HIGHLIGHT_KEY), (task, until)));
+}
+
+/// The colour to draw the task's border in if it is highlighted
+pub fn highlight_colour(ctx: &Context, task: TaskId) -> Option<Color32> {
+    let (highlighted, until) =
+        ctx.data(|data| data.get_temp::<(TaskId, f64)>(egui::Id::new(HIGHLIGHT_KEY)))?;
+    let remaining = until - ctx.input(|input| input.time);
+    if highlighted != task || remaining <= 0.0 {
+        return None;
+    }
+
+    // Makes sure the highlight is cleared even if nothing else causes a repaint
+    ctx.request_repaint_after(std::time::Duration::from_secs_f64(remaining));
+    Some(HIGHLIGHT_COLOUR)
+}
@@ -1,19 +1,126 @@
 //! This module contains the logic for the settings window
-use egui::{containers::Frame, style::Margin, Color32, Context, Rounding, Ui};
+use egui::{containers::Frame, style::Margin, Context, Rounding, Ui};
 
-use crate::app::list::ListWindow;
+use crate::app::actions::{self, Action};
+use crate::app::board_view::BoardView;
+use crate::app::shortcuts::{self, ShortcutAction, Shortcuts};
+use crate::app::{tags, theme};
+use std::path::PathBuf;
+
+use crate::history::DEFAULT_HISTORY_DEPTH;
+use crate::storage;
+use crate::task::List;
+use crate::theme::{Theme, ThemeChoice};
 
 const WINDOW_WIDTH: f32 = 250.0;
 
-pub fn draw_settings(ctx: &Context, lists: &[ListWindow]) -> Vec<ListWindow> {
-    let mut lists = lists.to_vec();
+/// The largest undo history the user can pick
+const MAX_HISTORY_DEPTH: usize = 1000;
+
+/// The key the storage path being typed is stored under in egui's memory
+const STORAGE_PATH_DRAFT_KEY: &str = "storage path draft";
+/// The width of the storage path textbox, leaving room for the apply button
+const STORAGE_PATH_WIDTH: f32 = 180.0;
+
+/// The user's preferences for the app
+#[derive(Clone, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Settings {
+    /// How many changes can be undone on each board
+    history_depth: usize,
+    /// The file the boards are stored in, the default path is used when empty
+    storage_path: String,
+    /// Whether reminders are also sent as desktop notifications
+    desktop_notifications: bool,
+    /// The keys bound to each keyboard shortcut
+    shortcuts: Shortcuts,
+    /// The theme the app is drawn in
+    theme: ThemeChoice,
+}
+
+impl Settings {
+    pub fn history_depth(&self) -> usize {
+        self.history_depth
+    }
+
+    pub fn set_history_depth(&mut self, history_depth: usize) {
+        self.history_depth = history_depth.min(MAX_HISTORY_DEPTH);
+    }
+
+    /// The file the boards are stored in, or None if there is nowhere to store them
+    pub fn storage_path(&self) -> Option<PathBuf> {
+        self.picked_storage_path().or_else(storage::default_path)
+    }
+
+    /// The file the user picked to store the boards in, or None if the default path is used
+    pub fn picked_storage_path(&self) -> Option<PathBuf> {
+        match self.storage_path.trim() {
+            "" => None,
+            path => Some(PathBuf::from(path)),
+        }
+    }
+
+    pub fn set_storage_path(&mut self, storage_path: String) {
+        self.storage_path = storage_path;
+    }
+
+    pub fn desktop_notifications(&self) -> bool {
+        self.desktop_notifications
+    }
+
+    pub fn set_desktop_notifications(&mut self, desktop_notifications: bool) {
+        self.desktop_notifications = desktop_notifications;
+    }
+
+    pub fn shortcuts(&self) -> &Shortcuts {
+        &self.shortcuts
+    }
+
+    pub fn shortcuts_mut(&mut self) -> &mut Shortcuts {
+        &mut self.shortcuts
+    }
+
+    pub fn theme(&self) -> &ThemeChoice {
+        &self.theme
+    }
+
+    pub fn set_theme(&mut self, theme: ThemeChoice) {
+        self.theme = theme;
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            history_depth: DEFAULT_HISTORY_DEPTH,
+            storage_path: String::new(),
+            desktop_notifications: false,
+            shortcuts: Shortcuts::default(),
+            theme: ThemeChoice::default(),
+        }
+    }
+}
+
+/// Draws the settings window, dispatching the changes the user makes as actions
+///
+/// # Arguments
+///
+/// * `ctx`      - The egui handle
+/// * `board`    - The current board, whose lists can be renamed, tagged and hidden
+/// * `settings` - The current settings
+/// * `themes`   - The user's themes, which can be picked along with the built in ones
+pub fn draw_settings(ctx: &Context, board: &BoardView, settings: &Settings, themes: &[Theme]) {
     egui::Window::new("Settings")
         .resizable(false)
         .show(ctx, |ui| {
-            lists = draw_lists(ui, lists.clone());
+            draw_lists(ui, board);
+            draw_history_depth(ui, settings.history_depth);
+            draw_storage_path(ui, settings.storage_path.clone());
+            draw_theme(ui, settings.theme.clone(), themes);
+            draw_desktop_notifications(ui, settings.desktop_notifications);
+            draw_shortcuts(ui, &settings.shortcuts);
             draw_version(ui);
         });
-    lists
 }
 
 /// The outer margin of the settings widget
@@ -25,9 +132,220 @@ const SETTINGS_ROUNDING: f32 = 5.0;
 /// The border width of the settings widget
 const SETTINGS_BORDER_WIDTH: f32 = 1.0;
 
+fn draw_history_depth(ui: &mut Ui, history_depth: usize) {
+    let mut history_depth = history_depth;
+    Frame::none()
+        .fill(theme::border(ui.ctx()))
+        .outer_margin(Margin::same(SETTINGS_OUTER_MARGIN))
+        .rounding(Rounding::same(SETTINGS_ROUNDING))
+        .show(ui, |ui| {
+            Frame::none()
+                .outer_margin(Margin::same(SETTINGS_BORDER_WIDTH))
+                .inner_margin(Margin::same(SETTINGS_INNER_MARGIN))
+                .rounding(Rounding::same(SETTINGS_ROUNDING))
+                .fill(theme::background(ui.ctx()))
+                .show(ui, |ui| {
+                    ui.set_width(WINDOW_WIDTH);
+                    ui.horizontal(|ui| {
+                        ui.label("Undo history");
+                        let changed = ui
+                            .add(
+                                egui::DragValue::new(&mut history_depth)
+                                    .clamp_range(0..=MAX_HISTORY_DEPTH)
+                                    .suffix(" changes"),
+                            )
+                            .changed();
+                        if changed {
+                            actions::dispatch(ui.ctx(), Action::SetHistoryDepth(history_depth));
+                        }
+                    });
+                });
+        });
+}
+
+/// Draws the textbox for the storage file. The path is only applied once the user presses enter or
+/// clicks apply, so that a partly typed path is never used
+fn draw_storage_path(ui: &mut Ui, storage_path: String) {
+    let draft_id = egui::Id::new(STORAGE_PATH_DRAFT_KEY);
+    let mut draft = ui
+        .data_mut(|data| data.get_temp::<String>(draft_id))
+        .unwrap_or_else(|| storage_path.clone());
+    let default_path = storage::default_path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_default();
+    Frame::none()
+        .fill(theme::border(ui.ctx()))
+        .outer_margin(Margin::same(SETTINGS_OUTER_MARGIN))
+        .rounding(Rounding::same(SETTINGS_ROUNDING))
+        .show(ui, |ui| {
+            Frame::none()
+                .outer_margin(Margin::same(SETTINGS_BORDER_WIDTH))
+                .inner_margin(Margin::same(SETTINGS_INNER_MARGIN))
+                .rounding(Rounding::same(SETTINGS_ROUNDING))
+                .fill(theme::background(ui.ctx()))
+                .show(ui, |ui| {
+                    ui.set_width(WINDOW_WIDTH);
+                    ui.label("Storage file");
+                    let mut apply = false;
+                    ui.horizontal(|ui| {
+                        Frame::none()
+                            .fill(theme::border(ui.ctx()))
+                            .inner_margin(Margin::same(SETTINGS_BORDER_WIDTH))
+                            .rounding(Rounding::same(SETTINGS_ROUNDING - 2.0))
+                            .show(ui, |ui| {
+                                let response = ui
+                                    .add(
+                                        egui::TextEdit::singleline(&mut draft)
+                                            .hint_text(default_path)
+                                            .desired_width(STORAGE_PATH_WIDTH),
+                                    )
+                                    .on_hover_text("Boards are loaded from and saved to this file");
+                                apply = response.lost_focus()
+                                    && ui.input(|input| input.key_pressed(egui::Key::Enter));
+                            });
+                        apply |= ui
+                            .add_enabled(draft != storage_path, egui::Button::new("Apply"))
+                            .clicked();
+                    });
+
+                    if apply && draft != storage_path {
+                        actions::dispatch(ui.ctx(), Action::SetStoragePath(draft.clone()));
+                        ui.data_mut(|data| data.remove::<String>(draft_id));
+                    } else {
+                        ui.data_mut(|data| data.insert_temp(draft_id, draft.clone()));
+                    }
+                });
+        });
+}
+
+fn draw_desktop_notifications(ui: &mut Ui, desktop_notifications: bool) {
+    let mut desktop_notifications = desktop_notifications;
+    Frame::none()
+        .fill(theme::border(ui.ctx()))
+        .outer_margin(Margin::same(SETTINGS_OUTER_MARGIN))
+        .rounding(Rounding::same(SETTINGS_ROUNDING))
+        .show(ui, |ui| {
+            Frame::none()
+                .outer_margin(Margin::same(SETTINGS_BORDER_WIDTH))
+                .inner_margin(Margin::same(SETTINGS_INNER_MARGIN))
+                .rounding(Rounding::same(SETTINGS_ROUNDING))
+                .fill(theme::background(ui.ctx()))
+                .show(ui, |ui| {
+                    ui.set_width(WINDOW_WIDTH);
+                    let changed = ui
+                        .checkbox(&mut desktop_notifications, "Desktop notifications")
+                        .on_hover_text("Reminders are also sent to the desktop over D-Bus")
+                        .changed();
+                    if changed {
+                        actions::dispatch(
+                            ui.ctx(),
+                            Action::SetDesktopNotifications(desktop_notifications),
+                        );
+                    }
+                });
+        });
+}
+
+/// Draws the picker for the theme, listing the user's themes after the built in ones
+fn draw_theme(ui: &mut Ui, theme: ThemeChoice, themes: &[Theme]) {
+    let themes_path = crate::theme::themes_path()
+        .map(|path| path.display().to_string())
+        .unwrap_or_default();
+    Frame::none()
+        .fill(theme::border(ui.ctx()))
+        .outer_margin(Margin::same(SETTINGS_OUTER_MARGIN))
+        .rounding(Rounding::same(SETTINGS_ROUNDING))
+        .show(ui, |ui| {
+            Frame::none()
+                .outer_margin(Margin::same(SETTINGS_BORDER_WIDTH))
+                .inner_margin(Margin::same(SETTINGS_INNER_MARGIN))
+                .rounding(Rounding::same(SETTINGS_ROUNDING))
+                .fill(theme::background(ui.ctx()))
+                .show(ui, |ui| {
+                    ui.set_width(WINDOW_WIDTH);
+                    ui.horizontal(|ui| {
+                        ui.label("Theme");
+                        egui::ComboBox::from_id_source("theme")
+                            .selected_text(theme.to_string())
+                            .show_ui(ui, |ui| {
+                                let custom = themes
+                                    .iter()
+                                    .map(|custom| ThemeChoice::Custom(custom.name.clone()));
+                                for choice in ThemeChoice::BUILT_IN.into_iter().chain(custom) {
+                                    let label = choice.to_string();
+                                    if ui.selectable_label(choice == theme, label).clicked() {
+                                        actions::dispatch(ui.ctx(), Action::SetTheme(choice));
+                                    }
+                                }
+                            });
+                        if ui
+                            .button("⟳")
+                            .on_hover_text(format!("Reload the themes in {}", themes_path))
+                            .clicked()
+                        {
+                            actions::dispatch(ui.ctx(), Action::ReloadThemes);
+                        }
+                    });
+                });
+        });
+}
+
+/// Draws the keys bound to each shortcut. Clicking a binding waits for the next keys pressed and
+/// binds them instead, or escape to leave the binding as it was
+fn draw_shortcuts(ui: &mut Ui, shortcuts: &Shortcuts) {
+    let rebinding = shortcuts::rebinding(ui.ctx());
+    if let Some(action) = rebinding {
+        match shortcuts::pressed_binding(ui.ctx()) {
+            Some(binding) if binding.shortcut().key == egui::Key::Escape => {
+                shortcuts::set_rebinding(ui.ctx(), None);
+            }
+            Some(binding) => {
+                actions::dispatch(ui.ctx(), Action::SetShortcut(action, binding));
+                shortcuts::set_rebinding(ui.ctx(), None);
+            }
+            None => (),
+        }
+    }
+
+    Frame::none()
+        .fill(theme::border(ui.ctx()))
+        .outer_margin(Margin::same(SETTINGS_OUTER_MARGIN))
+        .rounding(Rounding::same(SETTINGS_ROUNDING))
+        .show(ui, |ui| {
+            Frame::none()
+                .outer_margin(Margin::same(SETTINGS_BORDER_WIDTH))
+                .inner_margin(Margin::same(SETTINGS_INNER_MARGIN))
+                .rounding(Rounding::same(SETTINGS_ROUNDING))
+                .fill(theme::background(ui.ctx()))
+                .show(ui, |ui| {
+                    ui.set_width(WINDOW_WIDTH);
+                    ui.collapsing("Shortcuts", |ui| {
+                        egui::Grid::new("shortcut bindings")
+                            .num_columns(2)
+                            .show(ui, |ui| {
+                                for action in ShortcutAction::ALL {
+                                    ui.label(action.to_string());
+                                    let label = match rebinding == Some(action) {
+                                        true => "Press a key...".to_string(),
+                                        false => shortcuts.binding(action).format(ui.ctx()),
+                                    };
+                                    if ui.button(label).clicked() {
+                                        shortcuts::set_rebinding(ui.ctx(), Some(action));
+                                    }
+                                    ui.end_row();
+                                }
+                            });
+                        if ui.button("Reset Shortcuts").clicked() {
+                            actions::dispatch(ui.ctx(), Action::ResetShortcuts);
+                        }
+                    });
+                });
+        });
+}
+
 fn draw_version(ui: &mut Ui) {
     Frame::none()
-        .fill(Color32::LIGHT_GRAY)
+        .fill(theme::border(ui.ctx()))
         .outer_margin(Margin::same(SETTINGS_OUTER_MARGIN))
         .rounding(Rounding::same(SETTINGS_ROUNDING))
         .show(ui, |ui| {
@@ -35,7 +353,7 @@ fn draw_version(ui: &mut Ui) {
                 .outer_margin(Margin::same(SETTINGS_BORDER_WIDTH))
                 .inner_margin(Margin::same(SETTINGS_INNER_MARGIN))
                 .rounding(Rounding::same(SETTINGS_ROUNDING))
-                .fill(Color32::WHITE)
+                .fill(theme::background(ui.ctx()))
                 .show(ui, |ui| {
                     ui.set_width(WINDOW_WIDTH);
                     let version = env!("CARGO_PKG_VERSION");
@@ -45,10 +363,9 @@ fn draw_version(ui: &mut Ui) {
         });
 }
 
-fn draw_lists(ui: &mut Ui, lists: Vec<ListWindow>) -> Vec<ListWindow> {
-    let mut lists = lists;
+fn draw_lists(ui: &mut Ui, board: &BoardView) {
     Frame::none()
-        .fill(Color32::LIGHT_GRAY)
+        .fill(theme::border(ui.ctx()))
         .outer_margin(Margin::same(SETTINGS_OUTER_MARGIN))
         .rounding(Rounding::same(SETTINGS_ROUNDING))
         .show(ui, |ui| {
@@ -56,43 +373,51 @@ fn draw_lists(ui: &mut Ui, lists: Vec<ListWindow>) -> Vec<ListWindow> {
                 .outer_margin(Margin::same(SETTINGS_BORDER_WIDTH))
                 .inner_margin(Margin::same(SETTINGS_INNER_MARGIN))
                 .rounding(Rounding::same(SETTINGS_ROUNDING))
-                .fill(Color32::WHITE)
+                .fill(theme::background(ui.ctx()))
                 .show(ui, |ui| {
                     ui.set_width(WINDOW_WIDTH);
                     ui.label("Lists");
-                    lists = lists
-                        .iter()
-                        .map(|list| draw_list(ui, list.clone()))
-                        .collect();
+                    for list in board.board().lists() {
+                        draw_list(ui, list, board.list_visible(list.id()));
+                    }
                 });
         });
-    lists
 }
 
-fn draw_list(ui: &mut Ui, list: ListWindow) -> ListWindow {
-    let mut list = list;
-
+fn draw_list(ui: &mut Ui, list: &List, visible: bool) {
+    let id = list.id();
     ui.horizontal(|ui| {
         // Having a border frame here so that the button lines up with the text
         // edit field
         Frame::none()
-            .fill(Color32::WHITE)
+            .fill(theme::background(ui.ctx()))
             .outer_margin(Margin::symmetric(0.0, SETTINGS_OUTER_MARGIN))
             .inner_margin(Margin::same(SETTINGS_BORDER_WIDTH))
             .rounding(Rounding::same(SETTINGS_ROUNDING - 2.0))
             .show(ui, |ui| {
-                ui.checkbox(list.mut_visible(), "");
+                let mut visible = visible;
+                if ui.checkbox(&mut visible, "").changed() {
+                    actions::dispatch(ui.ctx(), Action::SetListVisible(id, visible));
+                }
             });
-        list.set_name(textfield(ui, list.name()));
+        let name = textfield(ui, list.name());
+        if name != list.name() {
+            actions::dispatch(ui.ctx(), Action::RenameList(id, name));
+        }
+    });
+    ui.indent(("list tags", id), |ui| {
+        let tags =
+            tags::draw_tags_editor(ui, egui::Id::new(("list tags", id)), list.tags().clone());
+        if &tags != list.tags() {
+            actions::dispatch(ui.ctx(), Action::SetListTags(id, tags));
+        }
     });
-
-    list
 }
 
 fn textfield(ui: &mut Ui, contents: String) -> String {
     let mut contents = contents;
     Frame::none()
-        .fill(Color32::LIGHT_GRAY)
+        .fill(theme::border(ui.ctx()))
         .outer_margin(Margin::symmetric(0.0, SETTINGS_OUTER_MARGIN))
         .inner_margin(Margin::same(SETTINGS_BORDER_WIDTH))
         .rounding(Rounding::same(SETTINGS_ROUNDING - 2.0))
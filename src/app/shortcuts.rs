@@ -0,0 +1,313 @@
+//! This module contains the keyboard shortcuts, the selected task they act on and the cheat
+//! sheet listing them
+use std::collections::BTreeMap;
+use std::fmt;
+
+use egui::{Align2, Context, Key, KeyboardShortcut, Modifiers, RichText};
+
+use crate::id::{ListId, TaskId};
+
+/// The key the selected list and task are stored under in egui's memory
+const SELECTION_KEY: &str = "selection";
+/// The key the action waiting for new keys to be pressed is stored under in egui's memory
+const REBINDING_KEY: &str = "rebinding shortcut";
+
+/// Enum to model the things that can be done with a keyboard shortcut
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+)]
+pub enum ShortcutAction {
+    /// Start typing a new task in the selected list
+    NewTask,
+    /// Complete the selected task, or reopen it if it is completed
+    ToggleCompleted,
+    NextTask,
+    PreviousTask,
+    NextList,
+    PreviousList,
+    /// Switch the selected list in and out of editing mode
+    ToggleEditing,
+    /// Show or hide the settings window
+    OpenSettings,
+    /// Delete the selected task, once the user confirms it
+    DeleteTask,
+    /// Show or hide the list of shortcuts
+    CheatSheet,
+    /// Find and run any action by name
+    CommandPalette,
+}
+
+impl ShortcutAction {
+    /// All the shortcut actions, in the order they are listed
+    pub const ALL: [ShortcutAction; 11] = [
+        ShortcutAction::NewTask,
+        ShortcutAction::ToggleCompleted,
+        ShortcutAction::NextTask,
+        ShortcutAction::PreviousTask,
+        ShortcutAction::NextList,
+        ShortcutAction::PreviousList,
+        ShortcutAction::ToggleEditing,
+        ShortcutAction::OpenSettings,
+        ShortcutAction::DeleteTask,
+        ShortcutAction::CheatSheet,
+        ShortcutAction::CommandPalette,
+    ];
+
+    /// The keys the action is bound to unless the user changes them
+    pub fn default_binding(&self) -> KeyBinding {
+        let key = match self {
+            ShortcutAction::NewTask => Key::N,
+            ShortcutAction::ToggleCompleted => Key::Space,
+            ShortcutAction::NextTask => Key::J,
+            ShortcutAction::PreviousTask => Key::K,
+            ShortcutAction::NextList => Key::L,
+            ShortcutAction::PreviousList => Key::H,
+            ShortcutAction::ToggleEditing => Key::E,
+            ShortcutAction::OpenSettings => Key::S,
+            ShortcutAction::DeleteTask => Key::Delete,
+            ShortcutAction::CheatSheet => Key::F1,
+            ShortcutAction::CommandPalette => {
+                return KeyBinding::new(Modifiers::COMMAND, Key::P);
+            }
+        };
+        KeyBinding::new(Modifiers::NONE, key)
+    }
+}
+
+impl fmt::Display for ShortcutAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let description = match self {
+            ShortcutAction::NewTask => "New task in the selected list",
+            ShortcutAction::ToggleCompleted => "Complete the selected task",
+            ShortcutAction::NextTask => "Select the next task",
+            ShortcutAction::PreviousTask => "Select the previous task",
+            ShortcutAction::NextList => "Select the next list",
+            ShortcutAction::PreviousList => "Select the previous list",
+            ShortcutAction::ToggleEditing => "Edit the selected list",
+            ShortcutAction::OpenSettings => "Open the settings",
+            ShortcutAction::DeleteTask => "Delete the selected task",
+            ShortcutAction::CheatSheet => "Show the shortcuts",
+            ShortcutAction::CommandPalette => "Open the command palette",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+/// A key along with the modifiers that have to be held with it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct KeyBinding {
+    modifiers: Modifiers,
+    key: Key,
+}
+
+impl KeyBinding {
+    pub fn new(modifiers: Modifiers, key: Key) -> Self {
+        Self { modifiers, key }
+    }
+
+    pub fn shortcut(&self) -> KeyboardShortcut {
+        KeyboardShortcut::new(self.modifiers, self.key)
+    }
+
+    /// Formats the binding the way the platform writes shortcuts, such as "Ctrl+J"
+    pub fn format(&self, ctx: &Context) -> String {
+        ctx.format_shortcut(&self.shortcut())
+    }
+}
+
+/// The keys bound to each shortcut action. Only the bindings the user has changed are stored, so
+/// new actions get their default binding
+#[derive(Clone, Debug, Default, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Shortcuts {
+    bindings: BTreeMap<ShortcutAction, KeyBinding>,
+}
+
+impl Shortcuts {
+    /// The keys bound to the action
+    pub fn binding(&self, action: ShortcutAction) -> KeyBinding {
+        self.bindings
+            .get(&action)
+            .copied()
+            .unwrap_or_else(|| action.default_binding())
+    }
+
+    /// Binds the keys to the action. If another action was bound to the same keys, it takes the
+    /// action's old binding so that no two actions share keys
+    pub fn set_binding(&mut self, action: ShortcutAction, binding: KeyBinding) {
+        let old_binding = self.binding(action);
+        let clashing = ShortcutAction::ALL
+            .into_iter()
+            .find(|other| *other != action && self.binding(*other) == binding);
+        if let Some(clashing) = clashing {
+            self.bindings.insert(clashing, old_binding);
+        }
+        self.bindings.insert(action, binding);
+    }
+
+    /// Puts every action back on its default binding
+    pub fn reset(&mut self) {
+        self.bindings.clear();
+    }
+}
+
+/// The list and task the shortcuts act on
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Selection {
+    pub list: Option<ListId>,
+    pub task: Option<TaskId>,
+}
+
+/// The list and task currently selected
+pub fn selection(ctx: &Context) -> Selection {
+    ctx.data(|data| data.get_temp(egui::Id::new(SELECTION_KEY)))
+        .unwrap_or_default()
+}
+
+pub fn set_selection(ctx: &Context, selection: Selection) {
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new(SELECTION_KEY), selection));
+}
+
+/// Whether the task is the one selected for the shortcuts to act on
+pub fn is_selected(ctx: &Context, task: TaskId) -> bool {
+    selection(ctx).task == Some(task)
+}
+
+/// The action waiting for the user to press the keys to bind to it, if there is one
+pub fn rebinding(ctx: &Context) -> Option<ShortcutAction> {
+    ctx.data(|data| data.get_temp(egui::Id::new(REBINDING_KEY)))
+        .flatten()
+}
+
+pub fn set_rebinding(ctx: &Context, action: Option<ShortcutAction>) {
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new(REBINDING_KEY), action));
+}
+
+/// The key pressed this frame along with the modifiers held with it, used to rebind a shortcut
+pub fn pressed_binding(ctx: &Context) -> Option<KeyBinding> {
+    ctx.input(|input| {
+        input.events.iter().find_map(|event| match event {
+            egui::Event::Key {
+                key,
+                pressed: true,
+                modifiers,
+                ..
+            } => Some(KeyBinding::new(*modifiers, *key)),
+            _ => None,
+        })
+    })
+}
+
+/// Steps through a sequence of items, stopping at either end
+///
+/// # Arguments
+///
+/// * `items`   - The items to step through
+/// * `current` - The current item, the first or last item is picked if it isn't in the items
+/// * `forward` - Whether to step to the next item rather than the previous one
+///
+/// # Returns
+///
+/// The item stepped to, or None if there are no items
+pub fn step<T: Copy + PartialEq>(items: &[T], current: Option<T>, forward: bool) -> Option<T> {
+    let position = current.and_then(|current| items.iter().position(|item| *item == current));
+    let index = match (position, forward) {
+        (Some(position), true) => (position + 1).min(items.len().saturating_sub(1)),
+        (Some(position), false) => position.saturating_sub(1),
+        (None, true) => 0,
+        (None, false) => items.len().saturating_sub(1),
+    };
+    items.get(index).copied()
+}
+
+/// Reads the shortcuts the user pressed this frame. Shortcuts are ignored while a text field has
+/// focus or a shortcut is being rebound, so that typing doesn't trigger them
+///
+/// # Arguments
+///
+/// * `ctx`       - The egui handle
+/// * `shortcuts` - The keys bound to each action
+///
+/// # Returns
+///
+/// The actions whose shortcuts were pressed
+pub fn read_shortcuts(ctx: &Context, shortcuts: &Shortcuts) -> Vec<ShortcutAction> {
+    if ctx.memory(|memory| memory.focus()).is_some() || rebinding(ctx).is_some() {
+        return vec![];
+    }
+
+    ShortcutAction::ALL
+        .into_iter()
+        .filter(|action| {
+            let shortcut = shortcuts.binding(*action).shortcut();
+            ctx.input_mut(|input| input.consume_shortcut(&shortcut))
+        })
+        .collect()
+}
+
+/// Draws the cheat sheet listing the shortcuts in the middle of the screen
+///
+/// # Arguments
+///
+/// * `ctx`       - The egui handle
+/// * `shortcuts` - The keys bound to each action
+/// * `open`      - Whether the cheat sheet is open, set to false if the user closes it
+pub fn draw_cheat_sheet(ctx: &Context, shortcuts: &Shortcuts, open: &mut bool) {
+    egui::Window::new("Shortcuts")
+        .open(open)
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            egui::Grid::new("cheat sheet")
+                .num_columns(2)
+                .striped(true)
+                .show(ui, |ui| {
+                    for action in ShortcutAction::ALL {
+                        ui.label(RichText::new(shortcuts.binding(action).format(ctx)).strong());
+                        ui.label(action.to_string());
+                        ui.end_row();
+                    }
+                });
+            ui.label(RichText::new("Shortcuts can be changed in the settings").weak());
+        });
+}
+
+/// Draws the prompt confirming the selected task should be deleted. Enter confirms and escape
+/// cancels, so the task can be deleted without the mouse
+///
+/// # Arguments
+///
+/// * `ctx`   - The egui handle
+/// * `title` - The title of the task to delete
+///
+/// # Returns
+///
+/// Some(true) if the task should be deleted, Some(false) if the user cancelled, or None if the
+/// user hasn't answered yet
+pub fn draw_confirm_delete_task(ctx: &Context, title: &str) -> Option<bool> {
+    let mut answer = None;
+    egui::Window::new("Delete Task")
+        .collapsible(false)
+        .resizable(false)
+        .anchor(Align2::CENTER_CENTER, egui::Vec2::ZERO)
+        .show(ctx, |ui| {
+            ui.label(format!("Delete \"{}\"?", title));
+            ui.horizontal(|ui| {
+                if ui.button("Yes").clicked() {
+                    answer = Some(true);
+                }
+                if ui.button("No").clicked() {
+                    answer = Some(false);
+                }
+            });
+        });
+
+    if ctx.input(|input| input.key_pressed(Key::Enter)) {
+        answer = Some(true);
+    }
+    if ctx.input(|input| input.key_pressed(Key::Escape)) {
+        answer = Some(false);
+    }
+    answer
+}
@@ -0,0 +1,148 @@
+//! This module contains the statistics window, which draws its charts with egui's painter
+use egui::{Align2, Color32, Context, FontId, Rounding, Sense, Stroke, Ui, Vec2};
+
+use crate::app::{list, theme};
+use crate::stats::Stats;
+
+const CHART_WIDTH: f32 = 300.0;
+const CHART_HEIGHT: f32 = 100.0;
+/// The gap between the bars of a chart
+const BAR_GAP: f32 = 4.0;
+/// The height of each bar in the open and done chart
+const LIST_BAR_HEIGHT: f32 = 14.0;
+const LABEL_FONT_SIZE: f32 = 10.0;
+
+const BAR_COLOUR: Color32 = Color32::from_rgb(66, 133, 244);
+const DONE_COLOUR: Color32 = Color32::from_rgb(52, 168, 83);
+
+/// Draws the statistics window
+///
+/// # Arguments
+///
+/// * `ctx`   - The egui handle
+/// * `stats` - The statistics of the current board
+/// * `open`  - Whether the window is open, set to false if the user closes it
+pub fn draw_stats(ctx: &Context, stats: &Stats, open: &mut bool) {
+    egui::Window::new("Stats")
+        .open(open)
+        .resizable(false)
+        .show(ctx, |ui| {
+            ui.horizontal(|ui| {
+                ui.label(format!("Current streak: {} days", stats.current_streak));
+                ui.separator();
+                ui.label(format!("Longest streak: {} days", stats.longest_streak));
+            });
+            let average = stats
+                .average_time_to_complete
+                .map_or("unknown".to_string(), list::format_duration);
+            ui.label(format!("Average time to complete: {}", average));
+            ui.separator();
+
+            ui.strong("Completed per day");
+            let days: Vec<(String, usize)> = stats
+                .per_day
+                .iter()
+                .map(|(day, count)| (day.format("%d").to_string(), *count))
+                .collect();
+            draw_bar_chart(ui, &days);
+            ui.separator();
+
+            ui.strong("Completed per week");
+            let weeks: Vec<(String, usize)> = stats
+                .per_week
+                .iter()
+                .map(|(week, count)| (week.format("%d/%m").to_string(), *count))
+                .collect();
+            draw_bar_chart(ui, &weeks);
+            ui.separator();
+
+            ui.strong("Open and done per list");
+            for list in &stats.lists {
+                ui.horizontal(|ui| {
+                    draw_split_bar(ui, list.open, list.done);
+                    ui.label(format!(
+                        "{} ({}/{})",
+                        list.name,
+                        list.done,
+                        list.open + list.done
+                    ));
+                });
+            }
+        });
+}
+
+/// Draws a vertical bar chart with a label under each bar and the count above it
+///
+/// # Arguments
+///
+/// * `ui`   - The UI to draw the chart onto
+/// * `bars` - The label and value of each bar
+fn draw_bar_chart(ui: &mut Ui, bars: &[(String, usize)]) {
+    let label_height = LABEL_FONT_SIZE + BAR_GAP;
+    let (rect, _) = ui.allocate_exact_size(
+        Vec2::new(CHART_WIDTH, CHART_HEIGHT + label_height * 2.0),
+        Sense::hover(),
+    );
+    if bars.is_empty() {
+        return;
+    }
+
+    let painter = ui.painter();
+    let font = FontId::proportional(LABEL_FONT_SIZE);
+    let text_colour = ui.visuals().text_color();
+    let max = bars
+        .iter()
+        .map(|(_, count)| *count)
+        .max()
+        .unwrap_or(0)
+        .max(1) as f32;
+    let bar_width = CHART_WIDTH / bars.len() as f32;
+    let baseline = rect.bottom() - label_height;
+
+    painter.hline(rect.x_range(), baseline, Stroke::new(1.0, text_colour));
+    for (index, (label, count)) in bars.iter().enumerate() {
+        let left = rect.left() + bar_width * index as f32;
+        let centre = left + bar_width / 2.0;
+        let height = CHART_HEIGHT * *count as f32 / max;
+        let bar = egui::Rect::from_min_max(
+            egui::pos2(left + BAR_GAP / 2.0, baseline - height),
+            egui::pos2(left + bar_width - BAR_GAP / 2.0, baseline),
+        );
+        painter.rect_filled(bar, Rounding::none(), BAR_COLOUR);
+        painter.text(
+            egui::pos2(centre, baseline + BAR_GAP / 2.0),
+            Align2::CENTER_TOP,
+            label,
+            font.clone(),
+            text_colour,
+        );
+        if *count > 0 {
+            painter.text(
+                egui::pos2(centre, bar.top() - BAR_GAP / 2.0),
+                Align2::CENTER_BOTTOM,
+                count.to_string(),
+                font.clone(),
+                text_colour,
+            );
+        }
+    }
+}
+
+/// Draws a horizontal bar split into the done and open parts of a list
+///
+/// # Arguments
+///
+/// * `ui`   - The UI to draw the bar onto
+/// * `open` - How many tasks are open
+/// * `done` - How many tasks are done
+fn draw_split_bar(ui: &mut Ui, open: usize, done: usize) {
+    let width = CHART_WIDTH / 2.0;
+    let (rect, _) = ui.allocate_exact_size(Vec2::new(width, LIST_BAR_HEIGHT), Sense::hover());
+    let total = (open + done).max(1) as f32;
+    let done_width = width * done as f32 / total;
+
+    let painter = ui.painter();
+    painter.rect_filled(rect, Rounding::none(), theme::border(ui.ctx()));
+    let done_rect = egui::Rect::from_min_size(rect.min, Vec2::new(done_width, rect.height()));
+    painter.rect_filled(done_rect, Rounding::none(), DONE_COLOUR);
+}
@@ -0,0 +1,89 @@
+//! This module contains the widgets for displaying and editing tags
+use egui::{containers::Frame, style::Margin, RichText, Rounding, Ui};
+
+use crate::app::theme;
+use crate::task::Tags;
+
+/// The rounding of the tag widgets
+const TAG_ROUNDING: f32 = 3.0;
+/// The border width of the tag widgets
+const TAG_BORDER_WIDTH: f32 = 1.0;
+/// The width of the textbox for adding a tag
+const TAG_INPUT_WIDTH: f32 = 80.0;
+
+/// Draws the tags as a row of small labels
+///
+/// # Arguments
+///
+/// * `ui`   - The UI to draw the tags on
+/// * `tags` - The tags to draw
+pub fn draw_tags(ui: &mut Ui, tags: &Tags) {
+    if tags.is_empty() {
+        return;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        for tag in tags.iter() {
+            ui.label(
+                RichText::new(format!("#{}", tag))
+                    .small()
+                    .color(theme::outline(ui.ctx())),
+            );
+        }
+    });
+}
+
+/// Draws the tags with buttons to remove them, and a textbox to add a new tag
+///
+/// # Arguments
+///
+/// * `ui`   - The UI to draw the editor on
+/// * `id`   - A unique id for the editor, used to remember the contents of the textbox
+/// * `tags` - The tags to edit
+///
+/// # Returns
+///
+/// The tags after user interaction
+pub fn draw_tags_editor(ui: &mut Ui, id: egui::Id, tags: Tags) -> Tags {
+    let mut tags = tags;
+    let mut tag_to_remove = None;
+
+    ui.horizontal_wrapped(|ui| {
+        for tag in tags.iter() {
+            if ui
+                .small_button(format!("#{} x", tag))
+                .on_hover_text("Remove tag")
+                .clicked()
+            {
+                tag_to_remove = Some(tag.clone());
+            }
+        }
+
+        let mut tag_to_add = ui
+            .data_mut(|data| data.get_temp::<String>(id))
+            .unwrap_or_default();
+        Frame::none()
+            .fill(theme::border(ui.ctx()))
+            .inner_margin(Margin::same(TAG_BORDER_WIDTH))
+            .rounding(Rounding::same(TAG_ROUNDING))
+            .show(ui, |ui| {
+                if ui
+                    .add(
+                        egui::TextEdit::singleline(&mut tag_to_add)
+                            .hint_text("Add tag")
+                            .desired_width(TAG_INPUT_WIDTH),
+                    )
+                    .lost_focus()
+                    && tags.insert(&tag_to_add)
+                {
+                    tag_to_add.clear();
+                }
+            });
+        ui.data_mut(|data| data.insert_temp(id, tag_to_add));
+    });
+
+    if let Some(tag) = tag_to_remove {
+        tags.remove(&tag);
+    }
+    tags
+}
@@ -0,0 +1,55 @@
+//! This module contains applying the theme to the app, and the colours the widgets are drawn in
+use egui::{Color32, Context, Visuals};
+
+use crate::theme::{Colour, Theme};
+
+/// The key the theme being drawn in is stored under in egui's memory
+const THEME_KEY: &str = "theme";
+
+/// Draws the app in the theme, changing egui's visuals if the theme has changed
+///
+/// # Arguments
+///
+/// * `ctx`   - The egui handle
+/// * `theme` - The theme to draw the app in
+pub fn apply(ctx: &Context, theme: &Theme) {
+    let mut visuals = match theme.dark {
+        true => Visuals::dark(),
+        false => Visuals::light(),
+    };
+    if let Some(window) = theme.window {
+        visuals.window_fill = colour32(window);
+        visuals.panel_fill = colour32(window);
+    }
+    visuals.override_text_color = theme.text.map(colour32);
+
+    if ctx.style().visuals != visuals {
+        ctx.set_visuals(visuals);
+    }
+    ctx.data_mut(|data| data.insert_temp(egui::Id::new(THEME_KEY), theme.clone()));
+}
+
+/// The theme the app is being drawn in
+fn current(ctx: &Context) -> Theme {
+    ctx.data(|data| data.get_temp(egui::Id::new(THEME_KEY)))
+        .unwrap_or_else(Theme::light)
+}
+
+/// The colour around each widget
+pub fn border(ctx: &Context) -> Color32 {
+    colour32(current(ctx).border)
+}
+
+/// The colour inside each widget
+pub fn background(ctx: &Context) -> Color32 {
+    colour32(current(ctx).background)
+}
+
+/// The colour of drop indicators and faint text
+pub fn outline(ctx: &Context) -> Color32 {
+    colour32(current(ctx).outline)
+}
+
+fn colour32(colour: Colour) -> Color32 {
+    Color32::from_rgb(colour.r, colour.g, colour.b)
+}
@@ -1,9 +1,15 @@
 //! This module contains the logic of the topbar
-use egui::{containers::Frame, style::Margin, Color32, FontId, RichText, Rounding, Ui};
+use std::path::PathBuf;
 
-use rand::Rng;
+use egui::{containers::Frame, style::Margin, FontId, RichText, Rounding, Ui};
 
-use crate::app::ListWindow;
+use crate::app::actions::{self, Action};
+use crate::app::board_view::Layout;
+use crate::app::theme;
+use crate::board::RunningTimer;
+use crate::format::Format;
+use crate::search::SearchOptions;
+use crate::task::{List, Tags};
 
 const TOPBAR_OUTER_MARGIN: f32 = 5.0;
 const TOPBAR_OUTER_MARGIN_SIDE: f32 = 2.5;
@@ -15,6 +21,33 @@ const TOPBAR_BORDER_WIDTH: f32 = 1.0;
 pub struct TopBar {
     list_to_add: AddListResult,
     show_settings: bool,
+    /// The tags to filter the shown lists by, every list is shown when empty
+    #[serde(default)]
+    active_tags: Tags,
+    /// The contents of the new board textbox
+    #[serde(default)]
+    board_to_add: String,
+    /// The contents of the import and export file path textbox
+    #[serde(default)]
+    file_path: String,
+    /// The format to import and export files in
+    #[serde(default)]
+    file_format: Format,
+    /// The contents of the search textbox
+    #[serde(default)]
+    search: String,
+    /// How the search is matched against tasks
+    #[serde(default)]
+    search_options: SearchOptions,
+    /// Whether the archive window is open
+    #[serde(default)]
+    show_archive: bool,
+    /// Whether the stats window is open
+    #[serde(default)]
+    show_stats: bool,
+    /// Whether the user is being asked to confirm deleting the current board
+    #[serde(skip)]
+    confirming_delete_board: bool,
 }
 
 impl TopBar {
@@ -22,32 +55,338 @@ impl TopBar {
     ///
     /// # Arguments
     ///
-    /// * `ui` - The UI to draw the topbar on
-    pub fn draw(&mut self, ui: &mut Ui) -> Option<ListWindow> {
+    /// * `ui`            - The UI to draw the topbar on
+    /// * `tags`          - All the tags attached to lists, to pick the active tags from
+    /// * `boards`        - The names of all the boards
+    /// * `current_board` - The position of the board being displayed
+    /// * `layout`        - How the current board is laid out
+    /// * `timer`         - The task being timed and the position of its board, if there is one
+    pub fn draw(
+        &mut self,
+        ui: &mut Ui,
+        tags: &Tags,
+        boards: &[String],
+        current_board: usize,
+        layout: Layout,
+        timer: Option<(usize, &RunningTimer)>,
+    ) {
+        let mut action = None;
         ui.horizontal(|ui| {
             draw_logo(ui);
+            let board_action = self.draw_board_switcher(ui, boards, current_board, layout);
             self.list_to_add = add_list(ui, &self.list_to_add.unwrap());
-            self.show_settings = draw_show_settings(ui, self.show_settings);
+            self.active_tags = draw_tag_selector(ui, tags, self.active_tags.clone());
+            self.draw_search(ui);
+            let file_action = self.draw_file_menu(ui);
+            let timer_action = timer.and_then(|(board, timer)| {
+                // The board is only named when the task is on another board
+                let board = boards.get(board).filter(|_| board != current_board);
+                draw_timer(ui, timer, board)
+            });
+            action = board_action.or(file_action).or(timer_action);
+            let toggles = [
+                ("Archive", Action::ToggleArchive),
+                ("Stats", Action::ToggleStats),
+                ("Settings", Action::ToggleSettings),
+            ];
+            for (label, toggle) in toggles {
+                if draw_toggle_button(ui, label) {
+                    action = Some(toggle);
+                }
+            }
         });
 
-        match self.list_to_add.clone() {
-            AddListResult::AddList(list) => {
-                self.list_to_add = AddListResult::ContinueTyping(String::new());
-                Some(
-                    ListWindow::builder()
-                        .name(list)
-                        .id(egui::Id::new(rand::thread_rng().gen_range(0..u64::MAX)))
-                        .build()
-                        .unwrap(),
-                )
-            }
-            AddListResult::ContinueTyping(_) => None,
+        if let Some(action) = action {
+            actions::dispatch(ui.ctx(), action);
         }
+
+        if let AddListResult::AddList(name) = self.list_to_add.clone() {
+            self.list_to_add = AddListResult::ContinueTyping(String::new());
+            // New lists join the active tags so they don't disappear as soon as they are added
+            let mut list = List::new(name);
+            list.tags_mut().extend(self.active_tags.iter().cloned());
+            actions::dispatch(ui.ctx(), Action::AddList(list));
+        }
+    }
+
+    /// This function draws the board switcher, along with the controls for adding, renaming and
+    /// deleting boards
+    ///
+    /// # Arguments
+    ///
+    /// * `ui`            - The UI to draw the switcher on
+    /// * `boards`        - The names of all the boards
+    /// * `current_board` - The position of the board being displayed
+    /// * `layout`        - How the current board is laid out
+    ///
+    /// # Returns
+    ///
+    /// The board action the user took, if any
+    fn draw_board_switcher(
+        &mut self,
+        ui: &mut Ui,
+        boards: &[String],
+        current_board: usize,
+        layout: Layout,
+    ) -> Option<Action> {
+        let mut action = None;
+        let current_name = boards.get(current_board).cloned().unwrap_or_default();
+
+        Frame::none()
+            .fill(theme::border(ui.ctx()))
+            .outer_margin(Margin::symmetric(
+                TOPBAR_OUTER_MARGIN_SIDE,
+                TOPBAR_OUTER_MARGIN,
+            ))
+            .rounding(Rounding::same(TOPBAR_ROUNDING))
+            .show(ui, |ui| {
+                Frame::none()
+                    .outer_margin(Margin::same(TOPBAR_BORDER_WIDTH))
+                    .inner_margin(Margin::same(TOPBAR_INNER_MARGIN))
+                    .rounding(Rounding::same(TOPBAR_ROUNDING))
+                    .fill(theme::background(ui.ctx()))
+                    .show(ui, |ui| {
+                        ui.menu_button(format!("Board: {}", current_name), |ui| {
+                            for (index, name) in boards.iter().enumerate() {
+                                if ui.selectable_label(index == current_board, name).clicked() {
+                                    action = Some(Action::SwitchBoard(index));
+                                    self.confirming_delete_board = false;
+                                    ui.close_menu();
+                                }
+                            }
+                            ui.separator();
+
+                            ui.label("Layout");
+                            ui.horizontal(|ui| {
+                                for option in Layout::ALL {
+                                    if ui
+                                        .selectable_label(option == layout, option.to_string())
+                                        .clicked()
+                                    {
+                                        action = Some(Action::SetLayout(option));
+                                    }
+                                }
+                            });
+
+                            ui.label("Rename Board");
+                            let mut name = current_name.clone();
+                            if ui.text_edit_singleline(&mut name).changed() {
+                                action = Some(Action::RenameBoard(name));
+                            }
+
+                            ui.label("Add Board");
+                            if ui
+                                .text_edit_singleline(&mut self.board_to_add)
+                                .on_hover_text("Add a new board")
+                                .lost_focus()
+                                && !self.board_to_add.is_empty()
+                            {
+                                action =
+                                    Some(Action::AddBoard(std::mem::take(&mut self.board_to_add)));
+                                ui.close_menu();
+                            }
+
+                            ui.separator();
+                            if ui
+                                .add_enabled(boards.len() > 1, egui::Button::new("Delete Board"))
+                                .on_disabled_hover_text("The last board can't be deleted")
+                                .clicked()
+                            {
+                                self.confirming_delete_board = true;
+                            }
+                            if self.confirming_delete_board {
+                                match draw_confirm_delete_board(ui, &current_name) {
+                                    Some(true) => {
+                                        action = Some(Action::DeleteBoard);
+                                        self.confirming_delete_board = false;
+                                        ui.close_menu();
+                                    }
+                                    Some(false) => self.confirming_delete_board = false,
+                                    None => (),
+                                }
+                            }
+                        });
+                    });
+            });
+
+        action
+    }
+
+    /// This function draws the menu for importing and exporting boards as files
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI to draw the menu on
+    ///
+    /// # Returns
+    ///
+    /// The file action the user took, if any
+    fn draw_file_menu(&mut self, ui: &mut Ui) -> Option<Action> {
+        let mut action = None;
+
+        Frame::none()
+            .fill(theme::border(ui.ctx()))
+            .outer_margin(Margin::symmetric(
+                TOPBAR_OUTER_MARGIN_SIDE,
+                TOPBAR_OUTER_MARGIN,
+            ))
+            .rounding(Rounding::same(TOPBAR_ROUNDING))
+            .show(ui, |ui| {
+                Frame::none()
+                    .outer_margin(Margin::same(TOPBAR_BORDER_WIDTH))
+                    .inner_margin(Margin::same(TOPBAR_INNER_MARGIN))
+                    .rounding(Rounding::same(TOPBAR_ROUNDING))
+                    .fill(theme::background(ui.ctx()))
+                    .show(ui, |ui| {
+                        ui.menu_button("Import/Export", |ui| {
+                            ui.horizontal(|ui| {
+                                for format in Format::ALL {
+                                    ui.radio_value(
+                                        &mut self.file_format,
+                                        format,
+                                        format.to_string(),
+                                    );
+                                }
+                            });
+
+                            ui.label("File");
+                            if ui.text_edit_singleline(&mut self.file_path).changed() {
+                                self.file_format = Format::from_path(self.file_path.as_ref());
+                            }
+                            let path = PathBuf::from(self.file_path.trim());
+                            let has_path = !self.file_path.trim().is_empty();
+
+                            ui.horizontal(|ui| {
+                                if ui
+                                    .add_enabled(has_path, egui::Button::new("Import"))
+                                    .clicked()
+                                {
+                                    action = Some(Action::Import(path.clone(), self.file_format));
+                                    ui.close_menu();
+                                }
+                                if ui
+                                    .add_enabled(has_path, egui::Button::new("Export Board"))
+                                    .clicked()
+                                {
+                                    action = Some(Action::Export(path, self.file_format));
+                                    ui.close_menu();
+                                }
+                            });
+
+                            if ui
+                                .add_enabled(has_path, egui::Button::new("Export Time Log"))
+                                .on_hover_text("Write the time spent on tasks to the file as CSV")
+                                .clicked()
+                            {
+                                action = Some(Action::ExportTimeLog(PathBuf::from(
+                                    self.file_path.trim(),
+                                )));
+                                ui.close_menu();
+                            }
+
+                            ui.separator();
+                            if ui.button("Copy Board to Clipboard").clicked() {
+                                action = Some(Action::CopyBoard(self.file_format));
+                                ui.close_menu();
+                            }
+                        });
+                    });
+            });
+
+        action
+    }
+
+    /// This function draws the search textbox along with the options for how it matches tasks
+    ///
+    /// # Arguments
+    ///
+    /// * `ui` - The UI to draw the search on
+    fn draw_search(&mut self, ui: &mut Ui) {
+        Frame::none()
+            .fill(theme::border(ui.ctx()))
+            .outer_margin(Margin::symmetric(
+                TOPBAR_OUTER_MARGIN_SIDE,
+                TOPBAR_OUTER_MARGIN,
+            ))
+            .rounding(Rounding::same(TOPBAR_ROUNDING))
+            .show(ui, |ui| {
+                Frame::none()
+                    .outer_margin(Margin::same(TOPBAR_BORDER_WIDTH))
+                    .inner_margin(Margin::same(TOPBAR_INNER_MARGIN))
+                    .rounding(Rounding::same(TOPBAR_ROUNDING))
+                    .fill(theme::background(ui.ctx()))
+                    .show(ui, |ui| {
+                        ui.label("Search");
+                        Frame::none()
+                            .fill(theme::border(ui.ctx()))
+                            .inner_margin(Margin::same(TOPBAR_BORDER_WIDTH))
+                            .rounding(Rounding::same(TOPBAR_ROUNDING - 2.0))
+                            .show(ui, |ui| {
+                                ui.text_edit_singleline(&mut self.search)
+                                    .on_hover_text("Search every list on the board");
+                            });
+                        ui.toggle_value(&mut self.search_options.case_sensitive, "Aa")
+                            .on_hover_text("Match case");
+                        ui.toggle_value(&mut self.search_options.fuzzy, "Fuzzy")
+                            .on_hover_text("Match letters spread out in the text");
+                    });
+            });
     }
 
     pub fn show_settings(&self) -> bool {
         self.show_settings
     }
+
+    pub fn show_settings_mut(&mut self) -> &mut bool {
+        &mut self.show_settings
+    }
+
+    /// The file and format picked in the import and export menu, if a file has been entered
+    pub fn file_target(&self) -> Option<(PathBuf, Format)> {
+        match self.file_path.trim() {
+            "" => None,
+            path => Some((PathBuf::from(path), self.file_format)),
+        }
+    }
+
+    /// Whether the archive window is open
+    pub fn show_archive(&self) -> bool {
+        self.show_archive
+    }
+
+    /// Returns a mutable reference to whether the archive window is open
+    pub fn show_archive_mut(&mut self) -> &mut bool {
+        &mut self.show_archive
+    }
+
+    /// Whether the stats window is open
+    pub fn show_stats(&self) -> bool {
+        self.show_stats
+    }
+
+    /// Returns a mutable reference to whether the stats window is open
+    pub fn show_stats_mut(&mut self) -> &mut bool {
+        &mut self.show_stats
+    }
+
+    /// The tags the shown lists are filtered by
+    pub fn active_tags(&self) -> &Tags {
+        &self.active_tags
+    }
+
+    /// Stops filtering the shown lists by tags
+    pub fn clear_active_tags(&mut self) {
+        self.active_tags = Tags::new();
+    }
+
+    /// The contents of the search textbox
+    pub fn search(&self) -> &str {
+        &self.search
+    }
+
+    pub fn search_options(&self) -> SearchOptions {
+        self.search_options
+    }
 }
 
 impl Default for TopBar {
@@ -55,10 +394,44 @@ impl Default for TopBar {
         TopBar {
             list_to_add: AddListResult::ContinueTyping(String::new()),
             show_settings: false,
+            active_tags: Tags::new(),
+            board_to_add: String::new(),
+            file_path: String::new(),
+            file_format: Format::default(),
+            search: String::new(),
+            search_options: SearchOptions::default(),
+            show_archive: false,
+            show_stats: false,
+            confirming_delete_board: false,
         }
     }
 }
 
+/// This function draws the prompt confirming the current board should be deleted
+///
+/// # Arguments
+///
+/// * `ui`   - The UI to draw the prompt onto
+/// * `name` - The name of the board
+///
+/// # Returns
+///
+/// Some(true) if the board should be deleted, Some(false) if the user cancelled, or None if the
+/// user hasn't answered yet
+fn draw_confirm_delete_board(ui: &mut Ui, name: &str) -> Option<bool> {
+    let mut answer = None;
+    ui.label(format!("Delete {} and all of its lists?", name));
+    ui.horizontal(|ui| {
+        if ui.button("Yes").clicked() {
+            answer = Some(true);
+        }
+        if ui.button("No").clicked() {
+            answer = Some(false);
+        }
+    });
+    answer
+}
+
 /// This function draws the "logo"
 ///
 /// # Arguments
@@ -66,7 +439,7 @@ impl Default for TopBar {
 /// * `ui` - The UI to draw the topbar on
 fn draw_logo(ui: &mut Ui) {
     Frame::none()
-        .fill(Color32::LIGHT_GRAY)
+        .fill(theme::border(ui.ctx()))
         .outer_margin(Margin::symmetric(
             TOPBAR_OUTER_MARGIN_SIDE,
             TOPBAR_OUTER_MARGIN,
@@ -77,22 +450,38 @@ fn draw_logo(ui: &mut Ui) {
                 .outer_margin(Margin::same(TOPBAR_BORDER_WIDTH))
                 .inner_margin(Margin::same(TOPBAR_INNER_MARGIN))
                 .rounding(Rounding::same(TOPBAR_ROUNDING))
-                .fill(Color32::WHITE)
+                .fill(theme::background(ui.ctx()))
                 .show(ui, |ui| {
                     ui.label(RichText::new("Rusty Taskboard").font(FontId::proportional(18.0)));
                 });
         });
 }
 
-/// This function draws the show settings button
+/// This function draws the task being timed, how long it has been timed for and a button to stop
+/// the timer
 ///
 /// # Arguments
 ///
-/// * `ui` - The UI to draw the topbar on
-fn draw_show_settings(ui: &mut Ui, show_settings: bool) -> bool {
-    let mut show_settings = show_settings;
+/// * `ui`    - The UI to draw the timer on
+/// * `timer` - The task being timed
+/// * `board` - The name of the board the task is on, if it isn't the current board
+///
+/// # Returns
+///
+/// The stop timer action if the user clicked stop
+fn draw_timer(ui: &mut Ui, timer: &RunningTimer, board: Option<&String>) -> Option<Action> {
+    let mut action = None;
+    let elapsed = chrono::Local::now().naive_local() - timer.started_at;
+    let seconds = elapsed.num_seconds().max(0);
+    let elapsed = format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    );
+
     Frame::none()
-        .fill(Color32::LIGHT_GRAY)
+        .fill(theme::border(ui.ctx()))
         .outer_margin(Margin::symmetric(
             TOPBAR_OUTER_MARGIN_SIDE,
             TOPBAR_OUTER_MARGIN,
@@ -103,15 +492,118 @@ fn draw_show_settings(ui: &mut Ui, show_settings: bool) -> bool {
                 .outer_margin(Margin::same(TOPBAR_BORDER_WIDTH))
                 .inner_margin(Margin::same(TOPBAR_INNER_MARGIN))
                 .rounding(Rounding::same(TOPBAR_ROUNDING))
-                .fill(Color32::WHITE)
+                .fill(theme::background(ui.ctx()))
                 .show(ui, |ui| {
-                    if ui.button("Settings").clicked() {
-                        show_settings = !show_settings;
+                    match board {
+                        Some(board) => {
+                            ui.label(format!("⏱ {} on {} {}", timer.title, board, elapsed))
+                        }
+                        None => ui.label(format!("⏱ {} {}", timer.title, elapsed)),
+                    };
+                    if ui.button("⏹").on_hover_text("Stop timing").clicked() {
+                        action = Some(Action::StopTimer);
                     }
                 });
         });
 
-    show_settings
+    action
+}
+
+/// This function draws a button that shows or hides a window
+///
+/// # Arguments
+///
+/// * `ui`    - The UI to draw the topbar on
+/// * `label` - The text on the button
+///
+/// # Returns
+///
+/// Whether the button was clicked
+fn draw_toggle_button(ui: &mut Ui, label: &str) -> bool {
+    let mut clicked = false;
+    Frame::none()
+        .fill(theme::border(ui.ctx()))
+        .outer_margin(Margin::symmetric(
+            TOPBAR_OUTER_MARGIN_SIDE,
+            TOPBAR_OUTER_MARGIN,
+        ))
+        .rounding(Rounding::same(TOPBAR_ROUNDING))
+        .show(ui, |ui| {
+            Frame::none()
+                .outer_margin(Margin::same(TOPBAR_BORDER_WIDTH))
+                .inner_margin(Margin::same(TOPBAR_INNER_MARGIN))
+                .rounding(Rounding::same(TOPBAR_ROUNDING))
+                .fill(theme::background(ui.ctx()))
+                .show(ui, |ui| {
+                    clicked = ui.button(label).clicked();
+                });
+        });
+
+    clicked
+}
+
+/// This function draws the tag selector, used to only show lists with the selected tags
+///
+/// # Arguments
+///
+/// * `ui`          - The UI to draw the selector on
+/// * `tags`        - All the tags that can be selected
+/// * `active_tags` - The currently selected tags
+///
+/// # Returns
+///
+/// The selected tags after user interaction
+fn draw_tag_selector(ui: &mut Ui, tags: &Tags, active_tags: Tags) -> Tags {
+    let mut active_tags = active_tags;
+    // Active tags are always offered so that a tag removed from every list can still be deselected
+    let mut options = tags.clone();
+    options.extend(active_tags.iter().cloned());
+
+    let label = match active_tags.is_empty() {
+        true => "All Tags".to_string(),
+        false => active_tags
+            .iter()
+            .map(|tag| format!("#{}", tag))
+            .collect::<Vec<String>>()
+            .join(" "),
+    };
+
+    Frame::none()
+        .fill(theme::border(ui.ctx()))
+        .outer_margin(Margin::symmetric(
+            TOPBAR_OUTER_MARGIN_SIDE,
+            TOPBAR_OUTER_MARGIN,
+        ))
+        .rounding(Rounding::same(TOPBAR_ROUNDING))
+        .show(ui, |ui| {
+            Frame::none()
+                .outer_margin(Margin::same(TOPBAR_BORDER_WIDTH))
+                .inner_margin(Margin::same(TOPBAR_INNER_MARGIN))
+                .rounding(Rounding::same(TOPBAR_ROUNDING))
+                .fill(theme::background(ui.ctx()))
+                .show(ui, |ui| {
+                    ui.menu_button(label, |ui| {
+                        if ui.button("Show All").clicked() {
+                            active_tags = Tags::new();
+                            ui.close_menu();
+                        }
+                        if options.is_empty() {
+                            ui.label("No lists have tags yet");
+                        }
+                        for tag in options.iter() {
+                            let mut active = active_tags.contains(tag);
+                            if ui.checkbox(&mut active, format!("#{}", tag)).changed() {
+                                match active {
+                                    true => active_tags.insert(tag),
+                                    false => active_tags.remove(tag),
+                                };
+                            }
+                        }
+                    });
+                });
+        });
+
+    active_tags
 }
 
 #[derive(Clone, serde::Deserialize, serde::Serialize)]
@@ -148,7 +640,7 @@ fn add_list(ui: &mut Ui, list_name: &str) -> AddListResult {
     let mut list_name = list_name.to_string();
     let mut add_list = false;
     Frame::none()
-        .fill(Color32::LIGHT_GRAY)
+        .fill(theme::border(ui.ctx()))
         .outer_margin(Margin::symmetric(
             TOPBAR_OUTER_MARGIN_SIDE,
             TOPBAR_OUTER_MARGIN,
@@ -159,11 +651,11 @@ fn add_list(ui: &mut Ui, list_name: &str) -> AddListResult {
                 .outer_margin(Margin::same(TOPBAR_BORDER_WIDTH))
                 .inner_margin(Margin::same(TOPBAR_INNER_MARGIN))
                 .rounding(Rounding::same(TOPBAR_ROUNDING))
-                .fill(Color32::WHITE)
+                .fill(theme::background(ui.ctx()))
                 .show(ui, |ui| {
                     ui.label("Add List");
                     Frame::none()
-                        .fill(Color32::LIGHT_GRAY)
+                        .fill(theme::border(ui.ctx()))
                         .inner_margin(Margin::same(TOPBAR_BORDER_WIDTH))
                         .rounding(Rounding::same(TOPBAR_ROUNDING - 2.0))
                         .show(ui, |ui| {
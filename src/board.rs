@@ -0,0 +1,696 @@
+//! This module contains the board, the UI independent collection of lists
+
+use std::collections::HashSet;
+
+use chrono::NaiveDateTime;
+use serde::Deserialize;
+
+use crate::id::{BoardId, ListId, TaskId};
+use crate::task::{List, Tags, Task};
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Board {
+    /// The persistent identifier of the board
+    #[serde(default)]
+    id: BoardId,
+    /// The name of the board
+    #[serde(default)]
+    name: String,
+    /// The lists on the board
+    #[serde(default, deserialize_with = "deserialize_lists")]
+    lists: Vec<List>,
+    /// The completed tasks that have been cleared from lists, oldest first
+    #[serde(default)]
+    archive: Vec<ArchivedTask>,
+}
+
+impl Board {
+    pub fn new(name: String) -> Self {
+        Self {
+            id: BoardId::new(),
+            name,
+            lists: vec![],
+            archive: vec![],
+        }
+    }
+
+    pub fn id(&self) -> BoardId {
+        self.id
+    }
+
+    /// A board with the same identifier and name, but without any lists or archived tasks
+    pub fn without_contents(&self) -> Self {
+        Self {
+            id: self.id,
+            ..Self::new(self.name())
+        }
+    }
+
+    /// A clone of the boards name
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    /// Adds a list to the end of the board, giving it and its tasks new identifiers if they
+    /// already exist on the board
+    pub fn add_list(&mut self, list: List) {
+        let mut list = list;
+        while self.position(list.id()).is_some() {
+            list.regenerate_id();
+        }
+        self.lists.push(list);
+        unique_task_ids(&mut self.lists);
+    }
+
+    /// Creates a new empty list with the given name at the end of the board
+    ///
+    /// # Returns
+    ///
+    /// A mutable reference to the newly created list
+    pub fn create_list(&mut self, name: String) -> &mut List {
+        self.add_list(List::new(name));
+        self.lists.last_mut().unwrap()
+    }
+
+    /// The position of the list with the given id on the board
+    pub fn position(&self, id: ListId) -> Option<usize> {
+        self.lists.iter().position(|list| list.id() == id)
+    }
+
+    pub fn list_by_id(&self, id: ListId) -> Option<&List> {
+        self.lists.iter().find(|list| list.id() == id)
+    }
+
+    pub fn list_by_id_mut(&mut self, id: ListId) -> Option<&mut List> {
+        self.lists.iter_mut().find(|list| list.id() == id)
+    }
+
+    /// Removes the list at the given position, returning it if it existed
+    pub fn remove_list(&mut self, index: usize) -> Option<List> {
+        if index >= self.lists.len() {
+            return None;
+        }
+        Some(self.lists.remove(index))
+    }
+
+    /// Renames the list at the given position
+    ///
+    /// # Returns
+    ///
+    /// Whether the list existed or not
+    pub fn rename_list(&mut self, index: usize, name: String) -> bool {
+        match self.lists.get_mut(index) {
+            Some(list) => {
+                list.set_name(name);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves the list at `from` to `to`, clamping `to` to the end of the board
+    ///
+    /// # Returns
+    ///
+    /// Whether a list was moved or not
+    pub fn move_list(&mut self, from: usize, to: usize) -> bool {
+        match self.remove_list(from) {
+            Some(list) => {
+                let to = to.min(self.lists.len());
+                self.lists.insert(to, list);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Moves a task into a list, which can be the list it is already in
+    ///
+    /// # Arguments
+    ///
+    /// * `task`  - The task to move
+    /// * `from`  - The list the task is in
+    /// * `to`    - The list to move the task into
+    /// * `index` - The position in `to` to insert the task before, counted before the task is
+    ///   removed from `from`
+    ///
+    /// # Returns
+    ///
+    /// Whether the task was moved or not
+    pub fn move_task(&mut self, task: TaskId, from: ListId, to: ListId, index: usize) -> bool {
+        if self.list_by_id(to).is_none() {
+            return false;
+        }
+        let Some(from_list) = self.list_by_id_mut(from) else {
+            return false;
+        };
+        let Some(position) = from_list.position(task) else {
+            return false;
+        };
+
+        if from == to {
+            let index = if index > position { index - 1 } else { index };
+            return from_list.move_task(position, index);
+        }
+
+        let task = from_list.remove(position).unwrap();
+        self.list_by_id_mut(to).unwrap().insert(index, task);
+        true
+    }
+
+    /// Whether a task with the given id is in one of the lists on the board
+    pub fn contains_task(&self, id: TaskId) -> bool {
+        self.lists.iter().any(|list| list.position(id).is_some())
+    }
+
+    pub fn list(&self, index: usize) -> Option<&List> {
+        self.lists.get(index)
+    }
+
+    pub fn list_mut(&mut self, index: usize) -> Option<&mut List> {
+        self.lists.get_mut(index)
+    }
+
+    /// Finds the first list with the given name
+    pub fn find_list(&self, name: &str) -> Option<&List> {
+        self.lists.iter().find(|list| list.name() == name)
+    }
+
+    /// Finds the first list with the given name, returning a mutable reference
+    pub fn find_list_mut(&mut self, name: &str) -> Option<&mut List> {
+        self.lists.iter_mut().find(|list| list.name() == name)
+    }
+
+    pub fn lists(&self) -> &[List] {
+        &self.lists
+    }
+
+    pub fn lists_mut(&mut self) -> &mut [List] {
+        &mut self.lists
+    }
+
+    pub fn archive(&self) -> &[ArchivedTask] {
+        &self.archive
+    }
+
+    pub fn set_archive(&mut self, archive: Vec<ArchivedTask>) {
+        self.archive = archive;
+    }
+
+    /// Moves the completed tasks in a list into the archive
+    ///
+    /// # Arguments
+    ///
+    /// * `list` - The list to clear the completed tasks from
+    /// * `now`  - The current time, recorded as when the tasks were archived
+    ///
+    /// # Returns
+    ///
+    /// How many tasks were archived
+    pub fn archive_completed(&mut self, list: ListId, now: NaiveDateTime) -> usize {
+        let Some(list) = self.list_by_id_mut(list) else {
+            return 0;
+        };
+
+        let (completed, open) = list
+            .tasks()
+            .iter()
+            .cloned()
+            .partition::<Vec<Task>, _>(|task| task.completed());
+        list.set_tasks(open);
+
+        let list = list.clone();
+        let archived = completed.len();
+        self.archive.extend(
+            completed
+                .into_iter()
+                .map(|task| ArchivedTask::new(task, &list, now)),
+        );
+        archived
+    }
+
+    /// Puts an archived task back at the end of the list it came from. If the list no longer
+    /// exists, a list with the same name is used, or created if there isn't one
+    ///
+    /// # Returns
+    ///
+    /// Whether there was a task at the given position in the archive
+    pub fn restore_archived(&mut self, index: usize) -> bool {
+        if index >= self.archive.len() {
+            return false;
+        }
+        let mut archived = self.archive.remove(index);
+        while self.contains_task(archived.task.id()) {
+            archived.task.regenerate_id();
+        }
+
+        let list = match self.position(archived.list) {
+            Some(position) => &mut self.lists[position],
+            None => match self
+                .lists
+                .iter()
+                .position(|list| list.name() == archived.list_name)
+            {
+                Some(position) => &mut self.lists[position],
+                None => self.create_list(archived.list_name),
+            },
+        };
+        list.add(archived.task);
+        true
+    }
+
+    /// Permanently removes the task at the given position in the archive, returning it if it
+    /// existed
+    pub fn purge_archived(&mut self, index: usize) -> Option<ArchivedTask> {
+        if index >= self.archive.len() {
+            return None;
+        }
+        Some(self.archive.remove(index))
+    }
+
+    /// The task being timed. If several are, the one started most recently is the one running
+    pub fn running_timer(&self) -> Option<RunningTimer> {
+        self.lists
+            .iter()
+            .flat_map(|list| {
+                list.iter().filter_map(|task| {
+                    Some(RunningTimer {
+                        list: list.id(),
+                        task: task.id(),
+                        title: task.title(),
+                        started_at: task.timer_started_at()?,
+                    })
+                })
+            })
+            .max_by_key(|timer| timer.started_at)
+    }
+
+    /// Stops the timers of every task except the one started most recently, so that only one
+    /// task is timed at once. The stopped timers end when the running one started
+    ///
+    /// # Returns
+    ///
+    /// How many timers were stopped
+    pub fn stop_other_timers(&mut self) -> usize {
+        match self.running_timer() {
+            Some(running) => self.stop_timers_except(&running),
+            None => 0,
+        }
+    }
+
+    /// Stops the timers of every task except the running one, which may be on another board.
+    /// The stopped timers end when the running one started
+    ///
+    /// # Arguments
+    ///
+    /// * `running` - The timer to leave running
+    ///
+    /// # Returns
+    ///
+    /// How many timers were stopped
+    pub fn stop_timers_except(&mut self, running: &RunningTimer) -> usize {
+        let mut stopped = 0;
+        for list in &mut self.lists {
+            let others: Vec<TaskId> = list
+                .iter()
+                .filter(|task| task.id() != running.task && task.timer_started_at().is_some())
+                .map(|task| task.id())
+                .collect();
+            for task in others {
+                if let Some(task) = list.task_by_id_mut(task) {
+                    task.stop_timer(running.started_at);
+                    stopped += 1;
+                }
+            }
+        }
+        stopped
+    }
+
+    /// Stops the timer of the task being timed, if there is one
+    pub fn stop_timer(&mut self, now: NaiveDateTime) {
+        let Some(running) = self.running_timer() else {
+            return;
+        };
+        if let Some(task) = self
+            .list_by_id_mut(running.list)
+            .and_then(|list| list.task_by_id_mut(running.task))
+        {
+            task.stop_timer(now);
+        }
+    }
+
+    /// All the tags attached to the lists on the board
+    pub fn list_tags(&self) -> Tags {
+        let mut tags = Tags::new();
+        for list in &self.lists {
+            tags.extend(list.tags().iter().cloned());
+        }
+        tags
+    }
+
+    /// The lists that have at least one of the given tags, or every list if no tags are given
+    pub fn lists_tagged<'a>(&'a self, tags: &'a Tags) -> impl Iterator<Item = &'a List> {
+        self.lists
+            .iter()
+            .filter(move |list| list.matches_tags(tags))
+    }
+}
+
+/// The task on a board that is being timed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunningTimer {
+    /// The list the task is in
+    pub list: ListId,
+    pub task: TaskId,
+    pub title: String,
+    pub started_at: NaiveDateTime,
+}
+
+/// A completed task that was cleared from a list, kept as a record of finished work
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct ArchivedTask {
+    task: Task,
+    /// The list the task was archived from
+    list: ListId,
+    /// The name of the list when the task was archived, used if the list no longer exists
+    list_name: String,
+    archived_at: NaiveDateTime,
+}
+
+impl ArchivedTask {
+    pub fn new(task: Task, list: &List, archived_at: NaiveDateTime) -> Self {
+        Self {
+            task,
+            list: list.id(),
+            list_name: list.name(),
+            archived_at,
+        }
+    }
+
+    pub fn task(&self) -> &Task {
+        &self.task
+    }
+
+    pub fn list(&self) -> ListId {
+        self.list
+    }
+
+    /// A clone of the name of the list the task was archived from
+    pub fn list_name(&self) -> String {
+        self.list_name.clone()
+    }
+
+    pub fn archived_at(&self) -> NaiveDateTime {
+        self.archived_at
+    }
+
+    /// When the task was completed, or when it was archived if that isn't known
+    pub fn completed_at(&self) -> NaiveDateTime {
+        self.task.completed_at().unwrap_or(self.archived_at)
+    }
+}
+
+/// Gives every task that shares its identifier with an earlier task in the lists a new one, as
+/// tasks are found by identifier across a whole board
+fn unique_task_ids(lists: &mut [List]) {
+    let mut ids = HashSet::new();
+    for task in lists.iter_mut().flat_map(|list| list.tasks_mut()) {
+        while !ids.insert(task.id()) {
+            task.regenerate_id();
+        }
+    }
+}
+
+/// Deserializes the lists on a board, giving tasks that share an identifier new ones
+fn deserialize_lists<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<List>, D::Error> {
+    let mut lists = Vec::<List>::deserialize(deserializer)?;
+    unique_task_ids(&mut lists);
+    Ok(lists)
+}
+
+impl IntoIterator for Board {
+    type Item = List;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.lists.into_iter()
+    }
+}
+
+impl FromIterator<List> for Board {
+    fn from_iter<T: IntoIterator<Item = List>>(iter: T) -> Self {
+        let mut board = Self::default();
+        for list in iter {
+            board.add_list(list);
+        }
+        board
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::NaiveDate;
+
+    #[test]
+    fn create_and_query_lists() {
+        let mut board = Board::new("Board".to_string());
+        board
+            .create_list("Todo".to_string())
+            .add(Task::builder().title("Task".to_string()).build().unwrap());
+        board.create_list("Done".to_string());
+
+        assert_eq!(board.lists().len(), 2);
+        assert_eq!(board.find_list("Todo").unwrap().len(), 1);
+        assert!(board.find_list("Missing").is_none());
+    }
+
+    #[test]
+    fn rename_move_and_remove_lists() {
+        let mut board: Board = ["One", "Two", "Three"]
+            .iter()
+            .map(|name| List::new(name.to_string()))
+            .collect();
+
+        assert!(board.rename_list(0, "First".to_string()));
+        assert!(!board.rename_list(3, "Missing".to_string()));
+        assert!(board.move_list(0, 10));
+        assert_eq!(board.remove_list(0).unwrap().name(), "Two");
+
+        let names: Vec<String> = board.lists().iter().map(|list| list.name()).collect();
+        assert_eq!(names, vec!["Three", "First"]);
+    }
+
+    #[test]
+    fn lists_are_filtered_by_tags() {
+        let mut board = Board::new("Board".to_string());
+        board
+            .create_list("Work".to_string())
+            .tags_mut()
+            .insert("work");
+        board
+            .create_list("Home".to_string())
+            .tags_mut()
+            .insert("home");
+        board.create_list("Untagged".to_string());
+
+        let active: Tags = ["work"].into_iter().collect();
+        let names: Vec<String> = board
+            .lists_tagged(&active)
+            .map(|list| list.name())
+            .collect();
+        assert_eq!(names, vec!["Work"]);
+        assert_eq!(board.lists_tagged(&Tags::new()).count(), 3);
+        assert_eq!(board.list_tags().len(), 2);
+    }
+
+    #[test]
+    fn list_ids_are_unique_and_survive_serialization() {
+        let list = List::new("List".to_string());
+        let board: Board = vec![list.clone(), list.clone()].into_iter().collect();
+        assert_ne!(board.lists()[0].id(), board.lists()[1].id());
+
+        let serialized = serde_json::to_string(&board).unwrap();
+        let deserialized: Board = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, board);
+        assert!(deserialized.list_by_id(list.id()).is_some());
+    }
+
+    #[test]
+    fn task_ids_are_unique_across_the_board() {
+        let task = Task::builder().title("Task".to_string()).build().unwrap();
+        let mut list = List::new("List".to_string());
+        list.add(task.clone());
+        let lists = vec![list.clone(), List::new("Other".to_string()), list];
+        let ids = |board: &Board| -> HashSet<TaskId> {
+            board
+                .lists()
+                .iter()
+                .flat_map(|list| list.iter().map(|task| task.id()))
+                .collect()
+        };
+
+        let board: Board = lists.clone().into_iter().collect();
+        assert_eq!(ids(&board).len(), 2);
+        assert!(board.contains_task(task.id()));
+
+        let file = format!(
+            "{{ \"name\": \"Board\", \"lists\": {} }}",
+            serde_json::to_string(&lists).unwrap()
+        );
+        let mut loaded: Board = serde_json::from_str(&file).unwrap();
+        assert_eq!(ids(&loaded).len(), 2);
+        assert_eq!(loaded.lists()[0].get(0).unwrap().id(), task.id());
+
+        let first = loaded.lists()[0].id();
+        loaded
+            .list_by_id_mut(first)
+            .unwrap()
+            .get_mut(0)
+            .unwrap()
+            .set_completed(true);
+        loaded.archive_completed(
+            first,
+            NaiveDate::from_ymd_opt(2023, 6, 1)
+                .unwrap()
+                .and_hms_opt(9, 0, 0)
+                .unwrap(),
+        );
+        loaded.list_by_id_mut(first).unwrap().add(task.clone());
+        assert!(loaded.restore_archived(0));
+        assert_eq!(ids(&loaded).len(), 3);
+    }
+
+    #[test]
+    fn tasks_move_within_and_between_lists() {
+        let mut board = Board::new("Board".to_string());
+        let list = board.create_list("Todo".to_string());
+        for title in ["One", "Two", "Three"] {
+            list.add(Task::builder().title(title.to_string()).build().unwrap());
+        }
+        board.create_list("Done".to_string());
+        let todo = board.lists()[0].id();
+        let done = board.lists()[1].id();
+        let one = board.lists()[0].tasks()[0].id();
+        let three = board.lists()[0].tasks()[2].id();
+
+        assert!(board.move_task(one, todo, todo, 2));
+        let titles: Vec<String> = board.lists()[0].iter().map(|task| task.title()).collect();
+        assert_eq!(titles, vec!["Two", "One", "Three"]);
+
+        assert!(board.move_task(three, todo, done, 0));
+        assert_eq!(board.lists()[0].len(), 2);
+        assert_eq!(board.lists()[1].tasks()[0].id(), three);
+
+        assert!(!board.move_task(three, todo, done, 0));
+        assert!(!board.move_task(one, todo, ListId::new(), 0));
+    }
+
+    #[test]
+    fn completed_tasks_are_archived_and_restored() {
+        let now = NaiveDate::from_ymd_opt(2023, 6, 10)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap();
+        let mut board = Board::new("Board".to_string());
+        let list = board.create_list("Todo".to_string());
+        list.add(Task::builder().title("Open".to_string()).build().unwrap());
+        list.add(
+            Task::builder()
+                .title("Done".to_string())
+                .completed(true)
+                .build()
+                .unwrap(),
+        );
+        let list = list.id();
+
+        assert_eq!(board.archive_completed(list, now), 1);
+        assert_eq!(board.lists()[0].len(), 1);
+        assert_eq!(board.archive()[0].list_name(), "Todo");
+        assert_eq!(board.archive()[0].completed_at(), now);
+
+        // Restoring into a list that was deleted recreates it by name
+        board.remove_list(0);
+        assert!(board.restore_archived(0));
+        assert!(board.archive().is_empty());
+        assert_eq!(board.find_list("Todo").unwrap().tasks()[0].title(), "Done");
+        assert!(!board.restore_archived(0));
+
+        assert_eq!(board.archive_completed(board.lists()[0].id(), now), 1);
+        assert!(board.purge_archived(0).is_some());
+        assert!(board.archive().is_empty());
+    }
+
+    #[test]
+    fn only_one_timer_runs_at_once() {
+        let at = |hour| {
+            NaiveDate::from_ymd_opt(2023, 6, 10)
+                .unwrap()
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+        };
+        let mut board = Board::new("Board".to_string());
+        board
+            .create_list("Todo".to_string())
+            .add(Task::builder().title("First".to_string()).build().unwrap());
+        board
+            .create_list("Doing".to_string())
+            .add(Task::builder().title("Second".to_string()).build().unwrap());
+        assert_eq!(board.running_timer(), None);
+
+        board
+            .list_mut(0)
+            .unwrap()
+            .get_mut(0)
+            .unwrap()
+            .start_timer(at(9));
+        board
+            .list_mut(1)
+            .unwrap()
+            .get_mut(0)
+            .unwrap()
+            .start_timer(at(10));
+        assert_eq!(board.running_timer().unwrap().title, "Second");
+
+        assert_eq!(board.stop_other_timers(), 1);
+        assert_eq!(board.stop_other_timers(), 0);
+        let first = board.list(0).unwrap().get(0).unwrap();
+        assert_eq!(first.time_spent(at(12)), chrono::Duration::hours(1));
+
+        board.stop_timer(at(11));
+        assert_eq!(board.running_timer(), None);
+        let second = board.list(1).unwrap().get(0).unwrap();
+        assert_eq!(second.time_spent(at(12)), chrono::Duration::hours(1));
+
+        // A timer started on another board stops this board's timer
+        board
+            .list_mut(0)
+            .unwrap()
+            .get_mut(0)
+            .unwrap()
+            .start_timer(at(12));
+        let mut other = Board::new("Other".to_string());
+        other
+            .create_list("Todo".to_string())
+            .add(Task::builder().title("Third".to_string()).build().unwrap());
+        other
+            .list_mut(0)
+            .unwrap()
+            .get_mut(0)
+            .unwrap()
+            .start_timer(at(13));
+        let running = other.running_timer().unwrap();
+        assert_eq!(other.stop_timers_except(&running), 0);
+        assert_eq!(board.stop_timers_except(&running), 1);
+        assert_eq!(board.running_timer(), None);
+        let first = board.list(0).unwrap().get(0).unwrap();
+        assert_eq!(first.time_spent(at(14)), chrono::Duration::hours(2));
+    }
+}
@@ -0,0 +1,342 @@
+//! This module contains the headless command line interface, which works on the stored boards
+//! without opening a window
+
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use chrono::Local;
+
+use crate::board::Board;
+use crate::format::Format;
+use crate::storage;
+use crate::task::Task;
+
+/// The usage message printed by the help command
+const USAGE: &str = "\
+Usage: rusty_taskboard [OPTIONS] [COMMAND]
+
+Opens the taskboard window when no command is given.
+
+Commands:
+  list                  List the lists on the board
+  show <list>           Show the tasks in a list
+  add <list> <title>    Add a task to a list, creating the list if needed
+  done <list> <task>    Complete a task, by its title or its number from show
+  export [format]       Print the board as markdown (the default) or todo.txt
+  help                  Print this message
+
+Options:
+  --file <path>         The storage file to use, instead of the one picked in the app's
+                        settings or the default
+  --board <name>        The board to use, instead of the first board";
+
+/// The name of the board created when the storage file has no boards
+const DEFAULT_BOARD: &str = "Main";
+
+/// A command given on the command line
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    List,
+    Show { list: String },
+    Add { list: String, title: String },
+    Done { list: String, task: String },
+    Export { format: Format },
+    Help,
+}
+
+/// The options given on the command line
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    /// The storage file to use
+    file: Option<PathBuf>,
+    /// The name of the board to use
+    board: Option<String>,
+}
+
+/// Parses the command line arguments, not including the program name
+///
+/// # Returns
+///
+/// The options and command, or a message describing what is wrong with the arguments
+pub fn parse(args: &[String]) -> Result<(Options, Command), String> {
+    let mut options = Options::default();
+    let mut positional = vec![];
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--file" => options.file = Some(PathBuf::from(option_value(&mut args, arg)?)),
+            "--board" => options.board = Some(option_value(&mut args, arg)?),
+            "-h" | "--help" => return Ok((options, Command::Help)),
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    let command = match positional
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<&str>>()
+        .as_slice()
+    {
+        ["list"] => Command::List,
+        ["show", list] => Command::Show {
+            list: list.to_string(),
+        },
+        ["add", list, title @ ..] if !title.is_empty() => Command::Add {
+            list: list.to_string(),
+            title: title.join(" "),
+        },
+        ["done", list, task @ ..] if !task.is_empty() => Command::Done {
+            list: list.to_string(),
+            task: task.join(" "),
+        },
+        ["export"] => Command::Export {
+            format: Format::Markdown,
+        },
+        ["export", format] => Command::Export {
+            format: parse_format(format)?,
+        },
+        ["help"] | [] => Command::Help,
+        [command, ..] => {
+            return Err(format!(
+                "Unknown command or wrong arguments for '{}', see 'rusty_taskboard help'",
+                command
+            ))
+        }
+    };
+
+    Ok((options, command))
+}
+
+/// Gets the value following an option
+fn option_value<'a>(
+    args: &mut impl Iterator<Item = &'a String>,
+    option: &str,
+) -> Result<String, String> {
+    args.next()
+        .cloned()
+        .ok_or_else(|| format!("{} needs a value", option))
+}
+
+/// Parses the name of an export format
+fn parse_format(format: &str) -> Result<Format, String> {
+    match format.to_lowercase().as_str() {
+        "markdown" | "md" => Ok(Format::Markdown),
+        "todo.txt" | "todotxt" | "txt" => Ok(Format::TodoTxt),
+        _ => Err(format!(
+            "Unknown format '{}', expected markdown or todo.txt",
+            format
+        )),
+    }
+}
+
+/// Runs a command on the stored boards
+///
+/// # Arguments
+///
+/// * `command` - The command to run
+/// * `board`   - The name of the board to run the command on, or None for the first board
+/// * `boards`  - The stored boards
+/// * `output`  - Where to write the command's output
+///
+/// # Returns
+///
+/// Whether the boards were changed and need saving, or a message describing why the command failed
+pub fn run(
+    command: &Command,
+    board: Option<&str>,
+    boards: &mut Vec<Board>,
+    output: &mut impl Write,
+) -> Result<bool, String> {
+    let write_error = |error: io::Error| error.to_string();
+
+    if *command == Command::Help {
+        writeln!(output, "{}", USAGE).map_err(write_error)?;
+        return Ok(false);
+    }
+
+    if boards.is_empty() && board.is_none() {
+        boards.push(Board::new(DEFAULT_BOARD.to_string()));
+    }
+    let board = match board {
+        Some(name) => boards
+            .iter_mut()
+            .find(|board| board.name() == name)
+            .ok_or_else(|| format!("There is no board called '{}'", name))?,
+        None => &mut boards[0],
+    };
+
+    match command {
+        Command::List => {
+            for list in board.lists() {
+                let completed = list.iter().filter(|task| task.completed()).count();
+                writeln!(output, "{} ({}/{})", list.name(), completed, list.len())
+                    .map_err(write_error)?;
+            }
+            Ok(false)
+        }
+        Command::Show { list } => {
+            let list = board
+                .find_list(list)
+                .ok_or_else(|| format!("There is no list called '{}'", list))?;
+            for (index, task) in list.iter().enumerate() {
+                let checkbox = if task.completed() { "[x]" } else { "[ ]" };
+                writeln!(output, "{}. {} {}", index + 1, checkbox, task.title())
+                    .map_err(write_error)?;
+                for line in task.description().lines() {
+                    writeln!(output, "       {}", line).map_err(write_error)?;
+                }
+            }
+            Ok(false)
+        }
+        Command::Add { list, title } => {
+            let task = Task::builder()
+                .title(title.clone())
+                .created_at(Local::now().naive_local())
+                .build()
+                .map_err(|error| error.to_string())?;
+            match board.find_list_mut(list) {
+                Some(list) => list.add(task),
+                None => board.create_list(list.clone()).add(task),
+            }
+            Ok(true)
+        }
+        Command::Done {
+            list: list_name,
+            task,
+        } => {
+            let list = board
+                .find_list_mut(list_name)
+                .ok_or_else(|| format!("There is no list called '{}'", list_name))?;
+            let index = list
+                .iter()
+                .position(|existing| existing.title() == *task)
+                .or_else(|| {
+                    task.parse::<usize>()
+                        .ok()
+                        .and_then(|number| number.checked_sub(1))
+                        .filter(|index| *index < list.len())
+                })
+                .ok_or_else(|| format!("There is no task '{}' in '{}'", task, list_name))?;
+            let now = Local::now().naive_local();
+            if let Some(task) = list.get_mut(index) {
+                let previous = task.clone();
+                task.set_completed(true);
+                task.update_timestamps(&previous, now);
+            }
+            list.schedule_recurrences(now);
+            Ok(true)
+        }
+        Command::Export { format } => {
+            write!(output, "{}", format.export(board)).map_err(write_error)?;
+            Ok(false)
+        }
+        Command::Help => Ok(false),
+    }
+}
+
+/// Runs the command line interface, loading and saving the storage file
+///
+/// # Arguments
+///
+/// * `args` - The command line arguments, not including the program name
+///
+/// # Returns
+///
+/// The exit code of the program
+pub fn main(args: &[String]) -> i32 {
+    match try_main(args) {
+        Ok(()) => 0,
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            1
+        }
+    }
+}
+
+/// Runs the command line interface, returning a message if anything goes wrong
+fn try_main(args: &[String]) -> Result<(), String> {
+    let (options, command) = parse(args)?;
+    // The storage file picked in the app's settings takes priority, as it does in the app
+    let path = options
+        .file
+        .or_else(storage::configured_path)
+        .or_else(storage::default_path)
+        .ok_or("There is no storage file, pass one with --file")?;
+
+    let mut boards = match storage::load(&path) {
+        Ok(boards) => boards,
+        Err(error) if error.kind() == io::ErrorKind::NotFound => vec![],
+        Err(error) => return Err(format!("Couldn't load {}: {}", path.display(), error)),
+    };
+
+    let changed = run(
+        &command,
+        options.board.as_deref(),
+        &mut boards,
+        &mut io::stdout(),
+    )?;
+
+    if changed {
+        storage::save(&path, &boards)
+            .map_err(|error| format!("Couldn't save {}: {}", path.display(), error))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(str::to_string).collect()
+    }
+
+    /// Runs the command line on the boards, returning what it printed
+    fn run_command(line: &str, boards: &mut Vec<Board>) -> Result<String, String> {
+        let (options, command) = parse(&args(line))?;
+        let mut output = vec![];
+        run(&command, options.board.as_deref(), boards, &mut output)?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn parse_works() {
+        let (options, command) = parse(&args("--board Work add Inbox Buy milk")).unwrap();
+        assert_eq!(options.board.as_deref(), Some("Work"));
+        assert_eq!(
+            command,
+            Command::Add {
+                list: "Inbox".to_string(),
+                title: "Buy milk".to_string()
+            }
+        );
+        assert_eq!(
+            parse(&args("export txt")).unwrap().1,
+            Command::Export {
+                format: Format::TodoTxt
+            }
+        );
+        assert!(parse(&args("add Inbox")).is_err());
+        assert!(parse(&args("frobnicate")).is_err());
+        assert!(parse(&args("--file")).is_err());
+    }
+
+    #[test]
+    fn add_done_and_show_work() {
+        let mut boards = vec![];
+        run_command("add Inbox Buy milk", &mut boards).unwrap();
+        run_command("add Inbox Walk the dog", &mut boards).unwrap();
+        run_command("done Inbox Buy milk", &mut boards).unwrap();
+        run_command("done Inbox 2", &mut boards).unwrap();
+
+        assert_eq!(
+            run_command("show Inbox", &mut boards).unwrap(),
+            "1. [x] Buy milk\n2. [x] Walk the dog\n"
+        );
+        assert_eq!(run_command("list", &mut boards).unwrap(), "Inbox (2/2)\n");
+        assert!(run_command("done Inbox 3", &mut boards).is_err());
+        assert!(run_command("show Missing", &mut boards).is_err());
+        assert!(run_command("--board Missing list", &mut boards).is_err());
+    }
+}
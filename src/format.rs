@@ -0,0 +1,67 @@
+//! This module contains the text formats boards can be imported from and exported to
+
+use std::path::Path;
+
+use crate::board::Board;
+use crate::{markdown, todotxt};
+
+/// A text format for boards
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Format {
+    /// GitHub style markdown checklists
+    #[default]
+    Markdown,
+    /// The todo.txt format
+    TodoTxt,
+}
+
+impl Format {
+    /// All the formats
+    pub const ALL: [Format; 2] = [Format::Markdown, Format::TodoTxt];
+
+    /// Guesses the format of a file from its name, todo.txt for `.txt` files and markdown otherwise
+    pub fn from_path(path: &Path) -> Format {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("txt") => Format::TodoTxt,
+            _ => Format::Markdown,
+        }
+    }
+
+    /// Imports a board from text in the format
+    pub fn import(&self, contents: &str) -> Board {
+        match self {
+            Format::Markdown => markdown::import(contents),
+            Format::TodoTxt => todotxt::import(contents),
+        }
+    }
+
+    /// Exports a board to text in the format
+    pub fn export(&self, board: &Board) -> String {
+        match self {
+            Format::Markdown => markdown::export_board(board),
+            Format::TodoTxt => todotxt::export(board),
+        }
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Format::Markdown => "Markdown",
+            Format::TodoTxt => "todo.txt",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn format_is_guessed_from_path() {
+        assert_eq!(Format::from_path(Path::new("todo.txt")), Format::TodoTxt);
+        assert_eq!(Format::from_path(Path::new("notes.md")), Format::Markdown);
+        assert_eq!(Format::from_path(Path::new("tasks")), Format::Markdown);
+    }
+}
@@ -0,0 +1,143 @@
+//! This module contains the undo and redo history, a UI independent record of previous states
+
+use std::collections::VecDeque;
+
+/// The number of states kept in the history by default
+pub const DEFAULT_HISTORY_DEPTH: usize = 100;
+
+/// A bounded undo and redo history of snapshots of some state
+#[derive(Clone, Debug)]
+pub struct History<T> {
+    /// The states that can be returned to by undoing, the most recent at the back
+    undo_stack: VecDeque<T>,
+    /// The states that can be returned to by redoing, the most recent at the back
+    redo_stack: Vec<T>,
+    /// The maximum number of states that can be undone
+    depth: usize,
+}
+
+impl<T> History<T> {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            undo_stack: VecDeque::new(),
+            redo_stack: vec![],
+            depth,
+        }
+    }
+
+    /// Records the state from before a change was made, discarding anything that could be redone
+    ///
+    /// # Arguments
+    ///
+    /// * `previous` - The state before the change
+    pub fn record(&mut self, previous: T) {
+        self.redo_stack.clear();
+        self.undo_stack.push_back(previous);
+        self.trim();
+    }
+
+    /// Undoes the most recent change
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The current state, which can be returned to by redoing
+    ///
+    /// # Returns
+    ///
+    /// The state to return to, or None if there is nothing to undo
+    pub fn undo(&mut self, current: T) -> Option<T> {
+        let previous = self.undo_stack.pop_back()?;
+        self.redo_stack.push(current);
+        Some(previous)
+    }
+
+    /// Redoes the most recently undone change
+    ///
+    /// # Arguments
+    ///
+    /// * `current` - The current state, which can be returned to by undoing
+    ///
+    /// # Returns
+    ///
+    /// The state to return to, or None if there is nothing to redo
+    pub fn redo(&mut self, current: T) -> Option<T> {
+        let next = self.redo_stack.pop()?;
+        self.undo_stack.push_back(current);
+        self.trim();
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Sets how many states can be undone, dropping the oldest states if there are too many
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth;
+        self.trim();
+    }
+
+    /// Drops the oldest states until the history fits within its depth
+    fn trim(&mut self) {
+        while self.undo_stack.len() > self.depth {
+            self.undo_stack.pop_front();
+        }
+    }
+}
+
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_DEPTH)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn undo_and_redo_work() {
+        let mut history = History::new(10);
+        history.record(1);
+        history.record(2);
+
+        assert_eq!(history.undo(3), Some(2));
+        assert_eq!(history.undo(2), Some(1));
+        assert_eq!(history.undo(1), None);
+        assert_eq!(history.redo(1), Some(2));
+        assert_eq!(history.redo(2), Some(3));
+        assert_eq!(history.redo(3), None);
+    }
+
+    #[test]
+    fn recording_clears_redo() {
+        let mut history = History::new(10);
+        history.record(1);
+        assert_eq!(history.undo(2), Some(1));
+        history.record(1);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn history_is_bounded_by_depth() {
+        let mut history = History::new(2);
+        history.record(1);
+        history.record(2);
+        history.record(3);
+
+        assert_eq!(history.undo(4), Some(3));
+        assert_eq!(history.undo(3), Some(2));
+        assert_eq!(history.undo(2), None);
+
+        history.set_depth(0);
+        assert!(!history.can_undo());
+    }
+}
@@ -0,0 +1,63 @@
+//! This module contains the persistent identifiers for boards, lists and tasks
+
+use std::fmt;
+
+use uuid::Uuid;
+
+/// Defines an identifier type backed by a random UUID
+macro_rules! id_type {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(
+            Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, serde::Deserialize, serde::Serialize,
+        )]
+        #[serde(transparent)]
+        pub struct $name(Uuid);
+
+        impl $name {
+            /// Generates a new random identifier
+            pub fn new() -> Self {
+                Self(Uuid::new_v4())
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+    };
+}
+
+id_type!(
+    /// The identifier of a board
+    BoardId
+);
+
+id_type!(
+    /// The identifier of a list
+    ListId
+);
+
+id_type!(
+    /// The identifier of a task
+    TaskId
+);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ids_survive_serialization() {
+        let id = TaskId::new();
+        let serialized = serde_json::to_string(&id).unwrap();
+        assert_eq!(serde_json::from_str::<TaskId>(&serialized).unwrap(), id);
+    }
+}
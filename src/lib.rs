@@ -3,4 +3,30 @@ extern crate derive_builder;
 
 pub mod app;
 
-mod task;
+pub mod board;
+
+pub mod cli;
+
+pub mod format;
+
+pub mod history;
+
+pub mod id;
+
+pub mod markdown;
+
+pub mod reminders;
+
+pub mod search;
+
+pub mod stats;
+
+pub mod storage;
+
+pub mod task;
+
+pub mod theme;
+
+pub mod timelog;
+
+pub mod todotxt;
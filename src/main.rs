@@ -1,7 +1,20 @@
 use rusty_taskboard::app::RustyTaskboardApp;
+use rusty_taskboard::cli;
 
 fn main() {
-    let native_options = eframe::NativeOptions::default();
+    // Running a command on the stored boards rather than opening the window
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::main(&args));
+    }
+
+    let native_options = eframe::NativeOptions {
+        // Reports the desktop's theme on macOS and Windows. Linux never reports one, so "Follow
+        // system" falls back to the light theme there. The visuals eframe sets when the desktop's
+        // theme changes are replaced by the app's theme on the next frame
+        follow_system_theme: true,
+        ..Default::default()
+    };
     match eframe::run_native(
         "Rusty Taskboards",
         native_options,
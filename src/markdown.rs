@@ -0,0 +1,266 @@
+//! This module contains the import and export of boards as GitHub style markdown checklists
+//!
+//! A board is written as a level one heading, each list as a level two heading, and each task as a
+//! checkbox with its description indented beneath it and its subtasks as nested checkboxes:
+//!
+//! ```markdown
+//! # Main
+//!
+//! ## Tasklist
+//!
+//! - [ ] Write the release notes
+//!   Mention the new storage file
+//!   - [x] Collect the changelog
+//! - [x] Tag the release
+//! ```
+//!
+//! When importing, files without level two headings use level one headings for lists instead, and
+//! checkboxes before the first heading are put in a list called "Imported".
+
+use crate::board::Board;
+use crate::task::{List, Subtask, Task};
+
+/// The name of the list tasks are put in when they come before any heading
+const UNNAMED_LIST: &str = "Imported";
+
+/// The indent of descriptions and subtasks beneath a task
+const INDENT: &str = "  ";
+
+/// Exports a board to markdown, with the board name as a heading above its lists
+pub fn export_board(board: &Board) -> String {
+    let mut markdown = format!("# {}\n", board.name());
+    for list in board.lists() {
+        markdown.push('\n');
+        markdown.push_str(&export_list(list));
+    }
+    markdown
+}
+
+/// Exports a list to markdown, with the list name as a heading above its tasks
+pub fn export_list(list: &List) -> String {
+    let mut markdown = format!("## {}\n\n", list.name());
+    for task in list.iter() {
+        markdown.push_str(&format!(
+            "- {} {}\n",
+            checkbox(task.completed()),
+            task.title()
+        ));
+        for line in task.description().lines() {
+            // Blank lines aren't indented, so they don't leave trailing whitespace
+            markdown.push_str(format!("{}{}", INDENT, line).trim_end());
+            markdown.push('\n');
+        }
+        for subtask in task.subtasks() {
+            markdown.push_str(&format!(
+                "{}- {} {}\n",
+                INDENT,
+                checkbox(subtask.completed()),
+                subtask.title()
+            ));
+        }
+    }
+    markdown
+}
+
+/// The markdown checkbox for a completion state
+fn checkbox(completed: bool) -> &'static str {
+    match completed {
+        true => "[x]",
+        false => "[ ]",
+    }
+}
+
+/// Imports a board from markdown
+///
+/// # Arguments
+///
+/// * `markdown` - The markdown to import
+///
+/// # Returns
+///
+/// A board with a list for every heading that has tasks beneath it, named after the level one
+/// heading if the lists use level two headings
+pub fn import(markdown: &str) -> Board {
+    let list_level = match markdown
+        .lines()
+        .any(|line| matches!(heading(line), Some((2, _))))
+    {
+        true => 2,
+        false => 1,
+    };
+
+    let mut board = Board::default();
+    let mut list: Option<List> = None;
+    // Blank lines are only part of a description when more of the description follows them
+    let mut blank_lines = 0;
+
+    for line in markdown.lines() {
+        if let Some((level, name)) = heading(line) {
+            blank_lines = 0;
+            if level == list_level {
+                push_list(&mut board, list.take());
+                list = Some(List::new(name.to_string()));
+            } else if level < list_level && board.name().is_empty() {
+                board.set_name(name.to_string());
+            }
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            blank_lines += 1;
+            continue;
+        }
+
+        let indented = line.starts_with(' ') || line.starts_with('\t');
+        let list = list.get_or_insert_with(|| List::new(UNNAMED_LIST.to_string()));
+
+        match (indented, checklist_item(line)) {
+            (false, Some((completed, title))) => {
+                if let Ok(task) = Task::builder()
+                    .title(title.to_string())
+                    .completed(completed)
+                    .build()
+                {
+                    list.add(task);
+                }
+            }
+            (true, Some((completed, title))) => {
+                if let Some(task) = last_task(list) {
+                    let mut subtask = Subtask::new(title.to_string());
+                    subtask.set_completed(completed);
+                    task.add_subtask(subtask);
+                }
+            }
+            (true, None) => {
+                if let Some(task) = last_task(list) {
+                    let mut description = task.description();
+                    if !description.is_empty() {
+                        description.push_str(&"\n".repeat(blank_lines + 1));
+                    }
+                    description.push_str(line.trim_start());
+                    task.set_description(description);
+                }
+            }
+            (false, None) => (),
+        }
+        blank_lines = 0;
+    }
+
+    push_list(&mut board, list);
+    board
+}
+
+/// Adds the list to the board if there is one
+fn push_list(board: &mut Board, list: Option<List>) {
+    if let Some(list) = list {
+        board.add_list(list);
+    }
+}
+
+/// Gets the last task in the list
+fn last_task(list: &mut List) -> Option<&mut Task> {
+    let last = list.len().checked_sub(1)?;
+    list.get_mut(last)
+}
+
+/// Parses a markdown heading
+///
+/// # Returns
+///
+/// The level and text of the heading, or None if the line isn't a heading
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    if level == 0 {
+        return None;
+    }
+    let text = line[level..].strip_prefix(' ')?;
+    Some((level, text.trim()))
+}
+
+/// Parses a markdown checklist item, ignoring any indentation
+///
+/// # Returns
+///
+/// Whether the item is checked and its text, or None if the line isn't a checklist item
+fn checklist_item(line: &str) -> Option<(bool, &str)> {
+    let line = line.trim_start();
+    let line = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|bullet| line.strip_prefix(bullet))?;
+    let (completed, title) = match line.get(..3)? {
+        "[ ]" => (false, &line[3..]),
+        "[x]" | "[X]" => (true, &line[3..]),
+        _ => return None,
+    };
+    Some((completed, title.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board() -> Board {
+        let mut board = Board::new("Main".to_string());
+        let list = board.create_list("Tasklist".to_string());
+        let mut task = Task::builder()
+            .title("Write notes".to_string())
+            .description("First line\n\nAfter a gap".to_string())
+            .build()
+            .unwrap();
+        let mut subtask = Subtask::new("Step".to_string());
+        subtask.set_completed(true);
+        task.add_subtask(subtask);
+        list.add(task);
+        list.add(
+            Task::builder()
+                .title("Done".to_string())
+                .completed(true)
+                .build()
+                .unwrap(),
+        );
+        board.create_list("Empty".to_string());
+        board
+    }
+
+    #[test]
+    fn export_works() {
+        let expected = "# Main\n\n## Tasklist\n\n- [ ] Write notes\n  First line\n\n  After a gap\n  - [x] Step\n- [x] Done\n\n## Empty\n\n";
+        assert_eq!(export_board(&board()), expected);
+    }
+
+    #[test]
+    fn round_trip_preserves_tasks() {
+        let board = board();
+        let imported = import(&export_board(&board));
+
+        assert_eq!(imported.name(), board.name());
+        assert_eq!(imported.lists().len(), board.lists().len());
+        for (imported, original) in imported.lists().iter().zip(board.lists()) {
+            assert_eq!(imported.name(), original.name());
+            let summary = |list: &List| -> Vec<(String, bool, String, Vec<Subtask>)> {
+                list.iter()
+                    .map(|task| {
+                        (
+                            task.title(),
+                            task.completed(),
+                            task.description(),
+                            task.subtasks().to_vec(),
+                        )
+                    })
+                    .collect()
+            };
+            assert_eq!(summary(imported), summary(original));
+        }
+    }
+
+    #[test]
+    fn import_without_list_headings() {
+        let markdown = "- [X] Loose task\n\n# Ideas\n\n* [ ] An idea\nSome prose\n";
+        let board = import(markdown);
+
+        let names: Vec<String> = board.lists().iter().map(|list| list.name()).collect();
+        assert_eq!(names, vec![UNNAMED_LIST, "Ideas"]);
+        assert!(board.lists()[0].get(0).unwrap().completed());
+        assert_eq!(board.lists()[1].get(0).unwrap().description(), "");
+    }
+}
@@ -0,0 +1,197 @@
+//! This module contains finding the reminders that have gone off on a board, and raising them as
+//! freedesktop notifications
+//!
+//! Desktop notifications are sent with `gdbus`, which calls `org.freedesktop.Notifications.Notify`
+//! on the session bus. The app sends them on a background thread, so a notification server that
+//! hangs or a missing `gdbus` never holds up drawing. They can be tested against a local bus
+//! started with `dbus-run-session -- cargo test -- --ignored`
+
+use std::io;
+use std::process::Command;
+use std::thread;
+
+use chrono::NaiveDateTime;
+
+use crate::board::Board;
+use crate::id::{ListId, TaskId};
+
+/// The name notifications are sent under
+const APP_NAME: &str = "Rusty Taskboard";
+/// How long a notification stays up for, -1 leaves it to the notification server
+const NOTIFICATION_TIMEOUT: &str = "int32 -1";
+/// How many seconds to wait for the notification server to answer
+const CALL_TIMEOUT: &str = "5";
+
+/// A task whose reminder has gone off
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DueReminder {
+    /// The list the task is in
+    pub list: ListId,
+    pub task: TaskId,
+    pub title: String,
+    /// When the reminder went off, which changes each time it is snoozed
+    pub goes_off_at: NaiveDateTime,
+}
+
+/// Finds the reminders on a board that have gone off and haven't been dismissed
+///
+/// # Arguments
+///
+/// * `board` - The board to find the reminders on
+/// * `now`   - The current time
+///
+/// # Returns
+///
+/// The due reminders, earliest first and otherwise in the order they are on the board
+pub fn due_reminders(board: &Board, now: NaiveDateTime) -> Vec<DueReminder> {
+    let mut due = vec![];
+    for list in board.lists() {
+        for task in list.iter().filter(|task| task.reminder_due(now)) {
+            if let Some(reminder) = task.reminder() {
+                due.push(DueReminder {
+                    list: list.id(),
+                    task: task.id(),
+                    title: task.title(),
+                    goes_off_at: reminder.goes_off_at(),
+                });
+            }
+        }
+    }
+    due.sort_by_key(|reminder| reminder.goes_off_at);
+    due
+}
+
+/// Sends a freedesktop notification over the D-Bus session bus
+///
+/// # Arguments
+///
+/// * `summary` - The heading of the notification
+/// * `body`    - The text of the notification
+///
+/// # Returns
+///
+/// An error if `gdbus` couldn't be run or the notification server rejected the call
+pub fn send_desktop_notification(summary: &str, body: &str) -> io::Result<()> {
+    let output = Command::new("gdbus")
+        .args([
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.Notifications",
+            "--object-path",
+            "/org/freedesktop/Notifications",
+            "--method",
+            "org.freedesktop.Notifications.Notify",
+            "--timeout",
+            CALL_TIMEOUT,
+            // Stops the -1 timeout being read as an option
+            "--",
+            APP_NAME,
+            "uint32 0",
+            "",
+            summary,
+            body,
+            // Typed, as gdbus can only work out the types when a notification server is running
+            "@as []",
+            "@a{sv} {}",
+            NOTIFICATION_TIMEOUT,
+        ])
+        .output()?;
+
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        )),
+    }
+}
+
+/// Sends a freedesktop notification on a background thread without waiting for it, printing
+/// any error rather than returning it
+///
+/// # Arguments
+///
+/// * `summary` - The heading of the notification
+/// * `body`    - The text of the notification
+pub fn spawn_desktop_notification(summary: String, body: String) {
+    thread::spawn(move || {
+        if let Err(error) = send_desktop_notification(&summary, &body) {
+            eprintln!("Couldn't send a desktop notification: {}", error);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::NaiveDate;
+
+    use crate::task::{Reminder, Task};
+
+    fn at(hour: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 6, 10)
+            .unwrap()
+            .and_hms_opt(hour, 0, 0)
+            .unwrap()
+    }
+
+    fn task(title: &str, completed: bool, reminder: Reminder) -> Task {
+        Task::builder()
+            .title(title.to_string())
+            .completed(completed)
+            .reminder(reminder)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn due_reminders_skip_completed_and_dismissed_tasks() {
+        let mut dismissed = Reminder::new(at(8));
+        dismissed.dismiss();
+        let mut snoozed = Reminder::new(at(7));
+        snoozed.snooze(at(9));
+
+        let mut board = Board::new("Board".to_string());
+        let list = board.create_list("Work".to_string());
+        list.add(task("Later", false, Reminder::new(at(12))));
+        list.add(task("Snoozed", false, snoozed));
+        list.add(task("Due", false, Reminder::new(at(8))));
+        list.add(task("Done", true, Reminder::new(at(8))));
+        list.add(task("Dismissed", false, dismissed));
+        list.add(Task::builder().title("None".to_string()).build().unwrap());
+
+        let titles: Vec<String> = due_reminders(&board, at(10))
+            .into_iter()
+            .map(|reminder| reminder.title)
+            .collect();
+        assert_eq!(titles, vec!["Due", "Snoozed"]);
+    }
+
+    #[test]
+    #[ignore = "needs a session bus, run with dbus-run-session -- cargo test -- --ignored"]
+    fn notifications_are_sent_over_the_session_bus() {
+        let mut monitor = Command::new("dbus-monitor")
+            .args(["--session", "interface=org.freedesktop.Notifications"])
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+            .expect("dbus-monitor should be installed");
+        thread::sleep(std::time::Duration::from_secs(1));
+
+        // Without a notification server the bus rejects the call, but it is still sent
+        let result = send_desktop_notification("Reminder", "Water the plants on Home");
+        thread::sleep(std::time::Duration::from_secs(1));
+        monitor.kill().unwrap();
+        let output = monitor.wait_with_output().unwrap();
+        let messages = String::from_utf8_lossy(&output.stdout);
+
+        assert!(
+            messages.contains("member=Notify"),
+            "{:?}: {}",
+            result,
+            messages
+        );
+        assert!(messages.contains("string \"Water the plants on Home\""));
+        assert!(messages.contains("int32 -1"));
+    }
+}
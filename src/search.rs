@@ -0,0 +1,178 @@
+//! This module contains searching for tasks by their title and description across a board
+
+use crate::board::Board;
+use crate::id::{ListId, TaskId};
+
+/// How a search query is matched against the text of tasks
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct SearchOptions {
+    /// Whether upper and lower case letters are treated as different letters
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// Whether the letters of the query can be spread out in the text, so that "rlsnt" finds
+    /// "release notes"
+    #[serde(default)]
+    pub fuzzy: bool,
+}
+
+/// A task that matched a search
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SearchMatch {
+    /// The list the task is in
+    pub list: ListId,
+    pub task: TaskId,
+    pub list_name: String,
+    pub title: String,
+    /// How closely the task matched, lower is closer
+    pub score: usize,
+}
+
+/// Scores how closely a query matches some text. Text containing the query scores 0, and fuzzy
+/// matches score one more than the number of letters skipped between the first and last letters
+/// of the query
+///
+/// # Arguments
+///
+/// * `query`   - What is being searched for
+/// * `text`    - The text to search in
+/// * `options` - How the query is matched
+///
+/// # Returns
+///
+/// The score, or None if the query doesn't match the text
+pub fn score(query: &str, text: &str, options: SearchOptions) -> Option<usize> {
+    let (query, text) = match options.case_sensitive {
+        true => (query.to_string(), text.to_string()),
+        false => (query.to_lowercase(), text.to_lowercase()),
+    };
+
+    if text.contains(&query) {
+        return Some(0);
+    }
+    if !options.fuzzy {
+        return None;
+    }
+
+    let mut text = text.chars();
+    let mut skipped = 0;
+    let mut started = false;
+    for letter in query.chars() {
+        loop {
+            if text.next()? == letter {
+                started = true;
+                break;
+            }
+            if started {
+                skipped += 1;
+            }
+        }
+    }
+    Some(skipped + 1)
+}
+
+/// Searches the titles and descriptions of every task on the board
+///
+/// # Arguments
+///
+/// * `board`   - The board to search
+/// * `query`   - What is being searched for, a blank query matches nothing
+/// * `options` - How the query is matched
+///
+/// # Returns
+///
+/// The matching tasks, closest match first and otherwise in the order they are on the board
+pub fn search(board: &Board, query: &str, options: SearchOptions) -> Vec<SearchMatch> {
+    let query = query.trim();
+    if query.is_empty() {
+        return vec![];
+    }
+
+    let mut matches = vec![];
+    for list in board.lists() {
+        for task in list.iter() {
+            let best = [task.title(), task.description()]
+                .iter()
+                .filter_map(|text| score(query, text, options))
+                .min();
+            if let Some(score) = best {
+                matches.push(SearchMatch {
+                    list: list.id(),
+                    task: task.id(),
+                    list_name: list.name(),
+                    title: task.title(),
+                    score,
+                });
+            }
+        }
+    }
+    matches.sort_by_key(|found| found.score);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::task::Task;
+
+    fn board() -> Board {
+        let mut board = Board::new("Board".to_string());
+        let list = board.create_list("Work".to_string());
+        for (title, description) in [
+            ("Write the Release Notes", ""),
+            ("Review pull requests", "Including the release branch"),
+            ("Water the plants", ""),
+        ] {
+            list.add(
+                Task::builder()
+                    .title(title.to_string())
+                    .description(description.to_string())
+                    .build()
+                    .unwrap(),
+            );
+        }
+        board
+    }
+
+    fn titles(matches: Vec<SearchMatch>) -> Vec<String> {
+        matches.into_iter().map(|found| found.title).collect()
+    }
+
+    #[test]
+    fn search_matches_titles_and_descriptions() {
+        let found = search(&board(), "release", SearchOptions::default());
+        assert_eq!(
+            titles(found),
+            vec!["Write the Release Notes", "Review pull requests"]
+        );
+
+        let options = SearchOptions {
+            case_sensitive: true,
+            ..SearchOptions::default()
+        };
+        assert_eq!(
+            titles(search(&board(), "release", options)),
+            vec!["Review pull requests"]
+        );
+        assert!(search(&board(), "  ", SearchOptions::default()).is_empty());
+    }
+
+    #[test]
+    fn fuzzy_matches_rank_after_exact_matches() {
+        assert_eq!(
+            score("rlsnt", "release notes", SearchOptions::default()),
+            None
+        );
+
+        let options = SearchOptions {
+            fuzzy: true,
+            ..SearchOptions::default()
+        };
+        assert_eq!(score("notes", "release notes", options), Some(0));
+        assert!(score("rlsnt", "release notes", options).is_some());
+        assert_eq!(score("xyz", "release notes", options), None);
+
+        let found = search(&board(), "wat", options);
+        assert_eq!(titles(found)[0], "Water the plants");
+    }
+}
@@ -0,0 +1,216 @@
+//! This module contains the statistics about the work done on a board
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::board::Board;
+
+/// How many days are counted in the tasks completed per day
+pub const DAYS: usize = 14;
+/// How many weeks are counted in the tasks completed per week
+pub const WEEKS: usize = 8;
+
+/// How many tasks in a list are open and done
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListStats {
+    pub name: String,
+    pub open: usize,
+    pub done: usize,
+}
+
+/// Statistics about the tasks on a board, including the archived ones
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of tasks completed on each of the last [`DAYS`] days, oldest first
+    pub per_day: Vec<(NaiveDate, usize)>,
+    /// The number of tasks completed in each of the last [`WEEKS`] weeks, keyed by the Monday
+    /// the week starts on, oldest first
+    pub per_week: Vec<(NaiveDate, usize)>,
+    /// The open and done tasks in each list
+    pub lists: Vec<ListStats>,
+    /// The average time from a task being created to it being completed, if any task has both
+    pub average_time_to_complete: Option<Duration>,
+    /// How many days in a row up to today, or yesterday if nothing has been completed today yet,
+    /// had a task completed
+    pub current_streak: usize,
+    /// The most days in a row that had a task completed
+    pub longest_streak: usize,
+}
+
+/// Works out the statistics for a board
+///
+/// # Arguments
+///
+/// * `board` - The board to work out the statistics of
+/// * `today` - The current date, which the days and weeks count back from
+pub fn stats(board: &Board, today: NaiveDate) -> Stats {
+    let mut completed = vec![];
+    let mut durations = vec![];
+    let tasks = board
+        .lists()
+        .iter()
+        .flat_map(|list| list.iter())
+        .chain(board.archive().iter().map(|archived| archived.task()));
+    for task in tasks {
+        durations.extend(task.time_to_complete());
+    }
+    for list in board.lists() {
+        completed.extend(
+            list.iter()
+                .filter(|task| task.completed())
+                .filter_map(|task| task.completed_at())
+                .map(|completed_at| completed_at.date()),
+        );
+    }
+    completed.extend(
+        board
+            .archive()
+            .iter()
+            .map(|archived| archived.completed_at().date()),
+    );
+    completed.sort();
+
+    let count_between = |start: NaiveDate, end: NaiveDate| {
+        completed
+            .iter()
+            .filter(|date| **date >= start && **date < end)
+            .count()
+    };
+
+    let per_day = (0..DAYS as i64)
+        .rev()
+        .map(|days_ago| {
+            let day = today - Duration::days(days_ago);
+            (day, count_between(day, day + Duration::days(1)))
+        })
+        .collect();
+
+    let this_week = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let per_week = (0..WEEKS as i64)
+        .rev()
+        .map(|weeks_ago| {
+            let week = this_week - Duration::weeks(weeks_ago);
+            (week, count_between(week, week + Duration::weeks(1)))
+        })
+        .collect();
+
+    let lists = board
+        .lists()
+        .iter()
+        .map(|list| {
+            let done = list.iter().filter(|task| task.completed()).count();
+            ListStats {
+                name: list.name(),
+                open: list.len() - done,
+                done,
+            }
+        })
+        .collect();
+
+    let average_time_to_complete = match durations.is_empty() {
+        true => None,
+        false => {
+            let total: Duration = durations.iter().fold(Duration::zero(), |sum, d| sum + *d);
+            Some(total / durations.len() as i32)
+        }
+    };
+
+    completed.dedup();
+    let (current_streak, longest_streak) = streaks(&completed, today);
+
+    Stats {
+        per_day,
+        per_week,
+        lists,
+        average_time_to_complete,
+        current_streak,
+        longest_streak,
+    }
+}
+
+/// Works out the current and longest runs of consecutive days
+///
+/// # Arguments
+///
+/// * `days`  - The days tasks were completed on, sorted without duplicates
+/// * `today` - The current date
+///
+/// # Returns
+///
+/// The current streak and the longest streak
+fn streaks(days: &[NaiveDate], today: NaiveDate) -> (usize, usize) {
+    let mut longest = 0;
+    let mut run = 0;
+    let mut previous: Option<NaiveDate> = None;
+    for day in days {
+        run = match previous {
+            Some(previous) if *day - previous == Duration::days(1) => run + 1,
+            _ => 1,
+        };
+        longest = longest.max(run);
+        previous = Some(*day);
+    }
+
+    let yesterday = today - Duration::days(1);
+    let current = match previous {
+        Some(last) if last == today || last == yesterday => run,
+        _ => 0,
+    };
+    (current, longest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::task::Task;
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 6, day).unwrap()
+    }
+
+    fn completed(title: &str, created: u32, completed: u32) -> Task {
+        Task::builder()
+            .title(title.to_string())
+            .completed(true)
+            .created_at(date(created).and_hms_opt(9, 0, 0).unwrap())
+            .completed_at(date(completed).and_hms_opt(9, 0, 0).unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn stats_count_completed_work() {
+        let mut board = Board::new("Board".to_string());
+        let list = board.create_list("Work".to_string());
+        list.add(completed("One", 1, 3));
+        list.add(completed("Two", 5, 6));
+        list.add(completed("Three", 6, 7));
+        list.add(Task::builder().title("Open".to_string()).build().unwrap());
+
+        // Wednesday the 7th of June
+        let stats = stats(&board, date(7));
+        assert_eq!(stats.per_day.len(), DAYS);
+        assert_eq!(stats.per_day.last(), Some(&(date(7), 1)));
+        assert_eq!(stats.per_week.last(), Some(&(date(5), 2)));
+        assert_eq!(
+            stats.lists,
+            vec![ListStats {
+                name: "Work".to_string(),
+                open: 1,
+                done: 3,
+            }]
+        );
+        // Two days, one day and one day
+        assert_eq!(stats.average_time_to_complete, Some(Duration::hours(32)));
+        assert_eq!(stats.current_streak, 2);
+        assert_eq!(stats.longest_streak, 2);
+    }
+
+    #[test]
+    fn streaks_end_when_a_day_is_missed() {
+        let days = [date(1), date(2), date(3), date(6)];
+        assert_eq!(streaks(&days, date(7)), (1, 3));
+        assert_eq!(streaks(&days, date(8)), (0, 3));
+        assert_eq!(streaks(&[], date(8)), (0, 0));
+    }
+}
@@ -0,0 +1,336 @@
+//! This module contains the file storage for boards
+//!
+//! Boards are stored as pretty printed JSON, so that they can be read, edited and kept under
+//! version control by hand. The file looks like this:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "boards": [
+//!     {
+//!       "id": "5d2a8c14-9e37-4f0b-b6a1-3c8e7d9f2a46",
+//!       "name": "Main",
+//!       "lists": [
+//!         {
+//!           "id": "0b6c1f2e-6a51-4c38-9b8e-2f0d3c6a7e10",
+//!           "name": "Tasklist",
+//!           "tags": ["work"],
+//!           "tasks": [
+//!             {
+//!               "id": "7f1e9a40-3c2d-4b6e-8f51-a2d4c6e8b0f3",
+//!               "completed": false,
+//!               "status": "Doing",
+//!               "title": "Write the release notes",
+//!               "description": "",
+//!               "due": { "date": "2023-06-10", "time": "17:00:00" },
+//!               "priority": "High",
+//!               "subtasks": [{ "completed": true, "title": "Collect the changelog" }],
+//!               "tags": [],
+//!               "created_at": "2023-06-01T09:00:00",
+//!               "modified_at": "2023-06-02T14:15:00",
+//!               "recurrence": { "Weekly": ["Mon", "Fri"] },
+//!               "reminder": { "at": "2023-06-05T09:00:00", "snoozed_until": null, "dismissed": false },
+//!               "time_log": [
+//!                 { "started_at": "2023-06-05T09:00:00", "stopped_at": "2023-06-05T10:30:00" }
+//!               ]
+//!             }
+//!           ]
+//!         }
+//!       ],
+//!       "archive": [
+//!         {
+//!           "task": { "completed": true, "title": "Update the dependencies" },
+//!           "list": "0b6c1f2e-6a51-4c38-9b8e-2f0d3c6a7e10",
+//!           "list_name": "Tasklist",
+//!           "archived_at": "2023-06-09T18:30:00"
+//!         }
+//!       ]
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! A board's `id` can be left out, in which case one is generated when the file is loaded.
+//! Every field of a task other than `title` can be left out, in which case it takes its default
+//! value, and a missing `id`, or one shared with another task on the board, is generated when the
+//! file is loaded. `status` is the kanban column of a task that isn't completed, one of `Todo` or
+//! `Doing`, as completed tasks are always `Done`.
+//! The `archive` holds completed tasks cleared from lists, along with the list they came from.
+//! `created_at`, `completed_at` and `modified_at` are kept up to date by the app as tasks change.
+//! `recurrence` is one of `"Daily"`, `{ "Weekly": [days] }`, `"Monthly"`, `{ "MonthlyOn": day }`
+//! or `{ "EveryDays": n }`. `"Monthly"` repeats on the day of the due date, and becomes
+//! `{ "MonthlyOn": day }` once the task repeats so that a short month doesn't move later ones.
+//! A `reminder` keeps when it was snoozed until and whether it was dismissed, so neither is lost
+//! when the app restarts.
+//! The `time_log` holds each session of work on a task, the last having no `stopped_at` while its
+//! timer is running.
+//!
+//! The app and the command line interface can use the same file at once. The app writes the file
+//! as soon as a board changes, and reloads it whenever something else writes to it rather than
+//! saving over the changes. Changes reloaded this way can't be undone in the app.
+//!
+//! A storage file picked in the app's settings is also written to `storage_path` in the app's
+//! config directory, so that the command line interface uses the same file as the app.
+
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::board::Board;
+
+/// The version of the storage file format written by this version of the app
+pub const STORAGE_VERSION: u32 = 1;
+
+/// The environment variable that overrides the default storage file path
+pub const STORAGE_PATH_VAR: &str = "RUSTY_TASKBOARD_FILE";
+
+/// The name of the storage file in the app's data directory
+const STORAGE_FILE_NAME: &str = "boards.json";
+
+/// The name of the file in the app's config directory holding the storage file picked in the
+/// app's settings
+const CONFIGURED_PATH_FILE_NAME: &str = "storage_path";
+
+/// The contents of the storage file
+#[derive(serde::Deserialize, serde::Serialize)]
+struct StorageFile {
+    /// The version of the format the file was written in
+    version: u32,
+    /// The boards stored in the file
+    boards: Vec<Board>,
+}
+
+/// The path boards are stored at when no other path has been configured
+///
+/// # Returns
+///
+/// The path in the `RUSTY_TASKBOARD_FILE` environment variable if it is set, otherwise
+/// `boards.json` in the app's data directory, or None if there is no data directory
+pub fn default_path() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(STORAGE_PATH_VAR) {
+        return Some(PathBuf::from(path));
+    }
+
+    directories_next::ProjectDirs::from("", "", "rusty_taskboard")
+        .map(|dirs| dirs.data_dir().join(STORAGE_FILE_NAME))
+}
+
+/// The path of the file holding the storage file picked in the app's settings
+///
+/// # Returns
+///
+/// `storage_path` in the app's config directory, or None if there is no config directory
+pub fn configured_path_file() -> Option<PathBuf> {
+    directories_next::ProjectDirs::from("", "", "rusty_taskboard")
+        .map(|dirs| dirs.config_dir().join(CONFIGURED_PATH_FILE_NAME))
+}
+
+/// The storage file picked in the app's settings
+///
+/// # Returns
+///
+/// The path in the config directory's `storage_path` file, or None if no storage file has been
+/// picked or the file can't be read
+pub fn configured_path() -> Option<PathBuf> {
+    load_configured_path(&configured_path_file()?)
+        .ok()
+        .flatten()
+}
+
+/// Loads the storage file picked in the app's settings
+///
+/// # Arguments
+///
+/// * `file` - The file holding the picked storage file
+///
+/// # Returns
+///
+/// The picked storage file, None if none has been picked, or an error if the file can't be read
+pub fn load_configured_path(file: &Path) -> io::Result<Option<PathBuf>> {
+    match fs::read_to_string(file) {
+        Ok(contents) => {
+            Ok(Some(PathBuf::from(contents.trim())).filter(|path| !path.as_os_str().is_empty()))
+        }
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+/// Saves the storage file picked in the app's settings, so that the command line interface uses
+/// it too
+///
+/// # Arguments
+///
+/// * `file` - The file to hold the picked storage file, its parent directories are created if
+///   needed
+/// * `path` - The picked storage file, or None to go back to the default path
+pub fn save_configured_path(file: &Path, path: Option<&Path>) -> io::Result<()> {
+    let Some(path) = path else {
+        return match fs::remove_file(file) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => Err(error),
+            _ => Ok(()),
+        };
+    };
+
+    if let Some(parent) = file.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(file, path.as_os_str().to_string_lossy().as_bytes())
+}
+
+/// Loads the boards stored in a file
+///
+/// # Arguments
+///
+/// * `path` - The path of the storage file
+///
+/// # Returns
+///
+/// The boards in the file, or an error if the file couldn't be read or isn't a valid storage file
+pub fn load(path: &Path) -> io::Result<Vec<Board>> {
+    let contents = fs::read_to_string(path)?;
+    let file: StorageFile = serde_json::from_str(&contents)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+    if file.version > STORAGE_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "{} was written by a newer version of Rusty Taskboard (format version {})",
+                path.display(),
+                file.version
+            ),
+        ));
+    }
+
+    Ok(file.boards)
+}
+
+/// When a storage file was last written
+///
+/// # Arguments
+///
+/// * `path` - The path of the storage file
+///
+/// # Returns
+///
+/// The modification time of the file, or an error if it doesn't exist or can't be read
+pub fn modified(path: &Path) -> io::Result<SystemTime> {
+    fs::metadata(path)?.modified()
+}
+
+/// Saves boards to a file, replacing its contents atomically so that the file is never left half
+/// written
+///
+/// # Arguments
+///
+/// * `path`   - The path of the storage file, its parent directories are created if needed
+/// * `boards` - The boards to store
+pub fn save(path: &Path, boards: &[Board]) -> io::Result<()> {
+    let file = StorageFile {
+        version: STORAGE_VERSION,
+        boards: boards.to_vec(),
+    };
+    let contents = serde_json::to_string_pretty(&file)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+
+    // Writing to a temporary file next to the real one means the rename can't cross filesystems
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut temp_file = fs::File::create(&temp_path)?;
+    temp_file.write_all(contents.as_bytes())?;
+    temp_file.sync_all()?;
+    fs::rename(&temp_path, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::task::Task;
+
+    /// A path in the temp directory that no other test uses
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("rusty_taskboard_{}", uuid::Uuid::new_v4()))
+            .join(STORAGE_FILE_NAME)
+    }
+
+    #[test]
+    fn boards_survive_save_and_load() {
+        let mut board = Board::new("Board".to_string());
+        board
+            .create_list("List".to_string())
+            .add(Task::builder().title("Task".to_string()).build().unwrap());
+        let path = temp_path();
+
+        save(&path, &[board.clone()]).unwrap();
+        assert_eq!(load(&path).unwrap(), vec![board]);
+
+        let mut temp_path = path.as_os_str().to_owned();
+        temp_path.push(".tmp");
+        assert!(!PathBuf::from(temp_path).exists());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn hand_written_files_load() {
+        let path = temp_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let contents = r#"{
+            "version": 1,
+            "boards": [{ "name": "Main", "lists": [{
+                "name": "List",
+                "tasks": [{ "completed": true, "title": "Task" }]
+            }] }]
+        }"#;
+        fs::write(&path, contents).unwrap();
+
+        let boards = load(&path).unwrap();
+        let task = boards[0].find_list("List").unwrap().find("Task").unwrap();
+        assert!(task.completed());
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn configured_paths_survive_save_and_load() {
+        let file = temp_path().with_file_name(CONFIGURED_PATH_FILE_NAME);
+        assert_eq!(load_configured_path(&file).unwrap(), None);
+
+        let path = PathBuf::from("/home/user/dotfiles/boards.json");
+        save_configured_path(&file, Some(&path)).unwrap();
+        assert_eq!(load_configured_path(&file).unwrap(), Some(path));
+
+        save_configured_path(&file, None).unwrap();
+        assert!(!file.exists());
+        save_configured_path(&file, None).unwrap();
+        assert_eq!(load_configured_path(&file).unwrap(), None);
+
+        fs::remove_dir_all(file.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn invalid_files_are_errors() {
+        let path = temp_path();
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        fs::write(&path, "not json").unwrap();
+        assert_eq!(load(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        fs::write(&path, r#"{ "version": 99, "boards": [] }"#).unwrap();
+        assert_eq!(load(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -1,19 +1,245 @@
-#[derive(Clone, serde::Deserialize, serde::Serialize)]
-pub struct List(Vec<Task>);
+//! This module contains the UI independent task and list model
+
+use std::collections::{BTreeSet, HashSet};
+
+use chrono::{Datelike, Months, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+
+use crate::id::{ListId, TaskId};
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct List {
+    /// The persistent identifier of the list
+    #[serde(default)]
+    id: ListId,
+    /// The name of the list
+    name: String,
+    /// The tasks in the list
+    #[serde(default)]
+    tasks: Vec<Task>,
+    /// The tags attached to the list
+    #[serde(default)]
+    tags: Tags,
+}
 
 impl List {
-    pub fn new() -> Self {
-        Self(vec![])
+    pub fn new(name: String) -> Self {
+        Self {
+            id: ListId::new(),
+            name,
+            tasks: vec![],
+            tags: Tags::new(),
+        }
+    }
+
+    pub fn id(&self) -> ListId {
+        self.id
+    }
+
+    /// Gives the list a freshly generated identifier
+    pub(crate) fn regenerate_id(&mut self) {
+        self.id = ListId::new();
+    }
+
+    /// A clone of the lists name
+    pub fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    pub fn set_name(&mut self, name: String) {
+        self.name = name;
+    }
+
+    pub fn tags(&self) -> &Tags {
+        &self.tags
+    }
+
+    pub fn tags_mut(&mut self) -> &mut Tags {
+        &mut self.tags
+    }
+
+    /// Whether the list has one of the given tags, or true if no tags are given
+    pub fn matches_tags(&self, tags: &Tags) -> bool {
+        tags.is_empty() || self.tags.intersects(tags)
     }
 
     pub fn add(&mut self, task: Task) {
-        self.0.push(task);
+        let task = self.with_unique_id(task);
+        self.tasks.push(task);
+    }
+
+    /// Inserts a task at the given position, clamping the position to the end of the list
+    pub fn insert(&mut self, index: usize, task: Task) {
+        let index = index.min(self.tasks.len());
+        let task = self.with_unique_id(task);
+        self.tasks.insert(index, task);
+    }
+
+    /// Gives the task a new identifier if one already exists in the list
+    fn with_unique_id(&self, task: Task) -> Task {
+        let mut task = task;
+        while self.position(task.id).is_some() {
+            task.regenerate_id();
+        }
+        task
+    }
+
+    /// The position of the task with the given id in the list
+    pub fn position(&self, id: TaskId) -> Option<usize> {
+        self.tasks.iter().position(|task| task.id == id)
+    }
+
+    /// Removes the task at the given position, returning it if it existed
+    pub fn remove(&mut self, index: usize) -> Option<Task> {
+        if index >= self.tasks.len() {
+            return None;
+        }
+        Some(self.tasks.remove(index))
+    }
+
+    /// Moves the task at `from` to `to`
+    ///
+    /// # Returns
+    ///
+    /// Whether a task was moved or not
+    pub fn move_task(&mut self, from: usize, to: usize) -> bool {
+        match self.remove(from) {
+            Some(task) => {
+                self.insert(to, task);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Task> {
+        self.tasks.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Task> {
+        self.tasks.get_mut(index)
+    }
+
+    pub fn task_by_id(&self, id: TaskId) -> Option<&Task> {
+        self.tasks.iter().find(|task| task.id == id)
+    }
+
+    pub fn task_by_id_mut(&mut self, id: TaskId) -> Option<&mut Task> {
+        self.tasks.iter_mut().find(|task| task.id == id)
+    }
+
+    /// Finds the first task with the given title
+    pub fn find(&self, title: &str) -> Option<&Task> {
+        self.tasks.iter().find(|task| task.title == title)
+    }
+
+    pub fn tasks(&self) -> &[Task] {
+        &self.tasks
+    }
+
+    pub fn tasks_mut(&mut self) -> &mut [Task] {
+        &mut self.tasks
+    }
+
+    /// Replaces the tasks in the list, keeping the list's name and identifier
+    pub fn set_tasks(&mut self, tasks: Vec<Task>) {
+        let mut ids = HashSet::new();
+        self.tasks = tasks;
+        for task in self.tasks.iter_mut() {
+            while !ids.insert(task.id) {
+                task.regenerate_id();
+            }
+        }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Task> {
+        self.tasks.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.tasks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tasks.is_empty()
+    }
+
+    /// Sorts the list so that completed tasks are at the top, keeping the relative order of tasks
+    pub fn sort_completed_first(&mut self) {
+        self.tasks.sort_by_key(|task| !task.completed);
+    }
+
+    /// Sorts the list by due date, earliest first, with tasks without a due date at the bottom
+    pub fn sort_by_due_date(&mut self) {
+        self.tasks
+            .sort_by_key(|task| (task.due.is_none(), task.due.map(|due| due.sort_key())));
+    }
+
+    /// Sorts the list by priority, most urgent first, with open tasks above completed tasks of the
+    /// same priority
+    pub fn sort_by_priority(&mut self) {
+        self.tasks
+            .sort_by_key(|task| (std::cmp::Reverse(task.priority), task.completed));
+    }
+
+    /// Sorts the list in the given order
+    pub fn sort(&mut self, order: SortOrder) {
+        match order {
+            SortOrder::CompletedFirst => self.sort_completed_first(),
+            SortOrder::DueDate => self.sort_by_due_date(),
+            SortOrder::Priority => self.sort_by_priority(),
+        }
+    }
+
+    /// Creates the next occurrence of every completed recurring task, straight after the completed
+    /// task. The recurrence moves to the new task, so each completion only creates one occurrence
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time, which the next occurrences are due after
+    ///
+    /// # Returns
+    ///
+    /// How many occurrences were created
+    pub fn schedule_recurrences(&mut self, now: NaiveDateTime) -> usize {
+        let mut scheduled = 0;
+        let mut index = 0;
+        while index < self.tasks.len() {
+            let task = &mut self.tasks[index];
+            if task.completed {
+                if let Some(next) = task.next_occurrence(now) {
+                    task.recurrence = None;
+                    self.insert(index + 1, next);
+                    scheduled += 1;
+                }
+            }
+            index += 1;
+        }
+        scheduled
+    }
+
+    /// Removes all the completed tasks from the list
+    pub fn remove_completed(&mut self) {
+        self.tasks.retain(|task| !task.completed);
     }
 
     /// The progress of the list as a percentage
     pub fn progress(&self) -> f32 {
-        let total_tasks = self.0.len() as f32;
-        let completed_tasks = self.0.iter().filter(|x| x.completed).count() as f32;
+        if self.tasks.is_empty() {
+            return 0.0;
+        }
+        let total_tasks = self.tasks.len() as f32;
+        let completed_tasks = self.tasks.iter().filter(|x| x.completed).count() as f32;
+        completed_tasks / total_tasks
+    }
+
+    /// The progress of the list as a percentage, where incomplete tasks with subtasks count
+    /// partially towards the progress by how many of their subtasks are complete
+    pub fn progress_with_subtasks(&self) -> f32 {
+        if self.tasks.is_empty() {
+            return 0.0;
+        }
+        let total_tasks = self.tasks.len() as f32;
+        let completed_tasks: f32 = self.tasks.iter().map(|task| task.progress()).sum();
         completed_tasks / total_tasks
     }
 }
@@ -23,30 +249,70 @@ impl IntoIterator for List {
     type IntoIter = std::vec::IntoIter<Self::Item>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.into_iter()
-    }
-}
-
-impl FromIterator<Task> for List {
-    fn from_iter<T: IntoIterator<Item = Task>>(iter: T) -> Self {
-        let mut list = Self::new();
-        for i in iter {
-            list.add(i);
-        }
-        list
+        self.tasks.into_iter()
     }
 }
 
-#[derive(Builder, Clone, serde::Deserialize, serde::Serialize)]
+#[derive(Builder, Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Task {
+    /// The persistent identifier of the task
+    #[builder(default = "TaskId::new()")]
+    #[serde(default)]
+    id: TaskId,
     /// Whether the task has been completed
     #[builder(default = "false")]
+    #[serde(default)]
     completed: bool,
+    /// The column the task is in while it isn't completed, see [`Task::status`]
+    #[builder(default = "Status::Todo")]
+    #[serde(default)]
+    status: Status,
     /// The title of the task
     title: String,
     /// The description of the task
     #[builder(default = "String::new()")]
+    #[serde(default)]
     description: String,
+    /// When the task is due, if ever
+    #[builder(default = "None", setter(strip_option))]
+    #[serde(default)]
+    due: Option<DueDate>,
+    /// How important the task is
+    #[builder(default = "Priority::None")]
+    #[serde(default)]
+    priority: Priority,
+    /// The checklist of steps in the task
+    #[builder(default = "vec![]")]
+    #[serde(default)]
+    subtasks: Vec<Subtask>,
+    /// The tags attached to the task
+    #[builder(default = "Tags::new()")]
+    #[serde(default)]
+    tags: Tags,
+    /// When the task was created, if known
+    #[builder(default = "None", setter(strip_option))]
+    #[serde(default)]
+    created_at: Option<NaiveDateTime>,
+    /// When the task was completed, if known
+    #[builder(default = "None", setter(strip_option))]
+    #[serde(default)]
+    completed_at: Option<NaiveDateTime>,
+    /// When the task was last changed, if known
+    #[builder(default = "None", setter(strip_option))]
+    #[serde(default)]
+    modified_at: Option<NaiveDateTime>,
+    /// How often the task repeats, if ever
+    #[builder(default = "None", setter(strip_option))]
+    #[serde(default)]
+    recurrence: Option<Recurrence>,
+    /// When to remind the user about the task, if ever
+    #[builder(default = "None", setter(strip_option))]
+    #[serde(default)]
+    reminder: Option<Reminder>,
+    /// The sessions of time spent working on the task, oldest first
+    #[builder(default = "vec![]")]
+    #[serde(default)]
+    time_log: Vec<TimeSession>,
 }
 
 impl Task {
@@ -54,15 +320,46 @@ impl Task {
         TaskBuilder::default()
     }
 
+    pub fn id(&self) -> TaskId {
+        self.id
+    }
+
+    /// Gives the task a freshly generated identifier
+    pub(crate) fn regenerate_id(&mut self) {
+        self.id = TaskId::new();
+    }
+
     pub fn completed(&self) -> bool {
         self.completed
     }
 
+    pub fn set_completed(&mut self, completed: bool) {
+        self.completed = completed;
+    }
+
     /// Gets a mutable reference to the completed field of the task
     pub fn mut_completed(&mut self) -> &mut bool {
         &mut self.completed
     }
 
+    /// The status of the task, which is always [`Status::Done`] once the task is completed
+    pub fn status(&self) -> Status {
+        match (self.completed, self.status) {
+            (true, _) => Status::Done,
+            (false, Status::Done) => Status::Todo,
+            (false, status) => status,
+        }
+    }
+
+    /// Sets the status of the task, completing it if the status is [`Status::Done`]. A task that
+    /// is completed and then uncompleted returns to the status it had before
+    pub fn set_status(&mut self, status: Status) {
+        self.completed = status == Status::Done;
+        if status != Status::Done {
+            self.status = status;
+        }
+    }
+
     /// A clone of the tasks title
     pub fn title(&self) -> String {
         self.title.clone()
@@ -80,12 +377,720 @@ impl Task {
     pub fn set_description(&mut self, description: String) {
         self.description = description;
     }
+
+    pub fn due(&self) -> Option<DueDate> {
+        self.due
+    }
+
+    pub fn set_due(&mut self, due: Option<DueDate>) {
+        self.due = due;
+    }
+
+    pub fn priority(&self) -> Priority {
+        self.priority
+    }
+
+    pub fn set_priority(&mut self, priority: Priority) {
+        self.priority = priority;
+    }
+
+    pub fn tags(&self) -> &Tags {
+        &self.tags
+    }
+
+    pub fn tags_mut(&mut self) -> &mut Tags {
+        &mut self.tags
+    }
+
+    pub fn created_at(&self) -> Option<NaiveDateTime> {
+        self.created_at
+    }
+
+    pub fn set_created_at(&mut self, created_at: Option<NaiveDateTime>) {
+        self.created_at = created_at;
+    }
+
+    pub fn completed_at(&self) -> Option<NaiveDateTime> {
+        self.completed_at
+    }
+
+    pub fn set_completed_at(&mut self, completed_at: Option<NaiveDateTime>) {
+        self.completed_at = completed_at;
+    }
+
+    pub fn modified_at(&self) -> Option<NaiveDateTime> {
+        self.modified_at
+    }
+
+    pub fn set_modified_at(&mut self, modified_at: Option<NaiveDateTime>) {
+        self.modified_at = modified_at;
+    }
+
+    /// Updates when the task was completed and last modified, based on how it has changed
+    ///
+    /// # Arguments
+    ///
+    /// * `previous` - The task before it was changed
+    /// * `now`      - The current time
+    pub fn update_timestamps(&mut self, previous: &Task, now: NaiveDateTime) {
+        if self == previous {
+            return;
+        }
+        if self.completed != previous.completed {
+            self.completed_at = match self.completed {
+                true => Some(now),
+                false => None,
+            };
+            // There is nothing left to time once a task is done
+            if self.completed {
+                self.stop_timer(now);
+            }
+        }
+        self.modified_at = Some(now);
+    }
+
+    /// How long the task took from being created to being completed, if both are known
+    pub fn time_to_complete(&self) -> Option<chrono::Duration> {
+        match (self.created_at, self.completed_at) {
+            (Some(created_at), Some(completed_at)) if self.completed => {
+                Some(completed_at - created_at)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn subtasks(&self) -> &[Subtask] {
+        &self.subtasks
+    }
+
+    /// Gets a mutable reference to the subtask at the given position
+    pub fn subtask_mut(&mut self, index: usize) -> Option<&mut Subtask> {
+        self.subtasks.get_mut(index)
+    }
+
+    pub fn add_subtask(&mut self, subtask: Subtask) {
+        self.subtasks.push(subtask);
+    }
+
+    /// Removes the subtask at the given position, returning it if it existed
+    pub fn remove_subtask(&mut self, index: usize) -> Option<Subtask> {
+        if index >= self.subtasks.len() {
+            return None;
+        }
+        Some(self.subtasks.remove(index))
+    }
+
+    /// How complete the task is between 0 and 1, counting completed subtasks if the task itself
+    /// hasn't been completed
+    pub fn progress(&self) -> f32 {
+        if self.completed {
+            return 1.0;
+        }
+        if self.subtasks.is_empty() {
+            return 0.0;
+        }
+        let completed_subtasks = self.subtasks.iter().filter(|x| x.completed).count() as f32;
+        completed_subtasks / self.subtasks.len() as f32
+    }
+
+    pub fn reminder(&self) -> Option<Reminder> {
+        self.reminder
+    }
+
+    pub fn set_reminder(&mut self, reminder: Option<Reminder>) {
+        self.reminder = reminder;
+    }
+
+    /// Gets a mutable reference to the reminder of the task, if it has one
+    pub fn reminder_mut(&mut self) -> Option<&mut Reminder> {
+        self.reminder.as_mut()
+    }
+
+    /// Whether the task's reminder should be shown, which it never is once the task is completed
+    pub fn reminder_due(&self, now: NaiveDateTime) -> bool {
+        !self.completed && self.reminder.is_some_and(|reminder| reminder.is_due(now))
+    }
+
+    pub fn time_log(&self) -> &[TimeSession] {
+        &self.time_log
+    }
+
+    /// When the running timer on the task was started, or None if it isn't being timed
+    pub fn timer_started_at(&self) -> Option<NaiveDateTime> {
+        self.time_log
+            .last()
+            .filter(|session| session.stopped_at.is_none())
+            .map(|session| session.started_at)
+    }
+
+    /// Starts timing the task, unless it is already being timed
+    pub fn start_timer(&mut self, now: NaiveDateTime) {
+        if self.timer_started_at().is_none() {
+            self.time_log.push(TimeSession {
+                started_at: now,
+                stopped_at: None,
+            });
+        }
+    }
+
+    /// Stops timing the task, if it is being timed
+    pub fn stop_timer(&mut self, now: NaiveDateTime) {
+        if let Some(session) = self
+            .time_log
+            .last_mut()
+            .filter(|session| session.stopped_at.is_none())
+        {
+            session.stopped_at = Some(now.max(session.started_at));
+        }
+    }
+
+    /// The total time spent on the task, including the running session
+    pub fn time_spent(&self, now: NaiveDateTime) -> chrono::Duration {
+        self.time_log
+            .iter()
+            .fold(chrono::Duration::zero(), |total, session| {
+                total + session.duration(now)
+            })
+    }
+
+    pub fn recurrence(&self) -> Option<&Recurrence> {
+        self.recurrence.as_ref()
+    }
+
+    pub fn set_recurrence(&mut self, recurrence: Option<Recurrence>) {
+        self.recurrence = recurrence;
+    }
+
+    /// Creates the next occurrence of a recurring task, which is a fresh copy of the task due on
+    /// the next date in the recurrence after its current due date. Occurrences missed in the past
+    /// are skipped, so the next occurrence is never due before today
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - The current time, used as the due date of tasks without one
+    ///
+    /// # Returns
+    ///
+    /// The next occurrence, or None if the task doesn't repeat
+    pub fn next_occurrence(&self, now: NaiveDateTime) -> Option<Task> {
+        let today = now.date();
+        let due = self.due.map_or(today, |due| due.date);
+        // Monthly tasks keep the day they were due on, so a short month doesn't move later ones
+        let recurrence = match self.recurrence.as_ref()? {
+            Recurrence::Monthly => Recurrence::MonthlyOn(due.day()),
+            recurrence => recurrence.clone(),
+        };
+        let mut date = recurrence.next_after(due);
+        while date < today {
+            date = recurrence.next_after(date);
+        }
+
+        let mut next = self.clone();
+        next.id = TaskId::new();
+        next.completed = false;
+        next.status = Status::Todo;
+        next.due = Some(DueDate::new(date, self.due.and_then(|due| due.time)));
+        next.recurrence = Some(recurrence);
+        next.created_at = Some(now);
+        next.completed_at = None;
+        next.modified_at = None;
+        next.time_log = vec![];
+        // The reminder moves along with the due date, keeping the same time. Without a due date it
+        // goes off on the day of the next occurrence. Either way it hasn't been snoozed or dismissed
+        next.reminder = self.reminder.map(|reminder| match self.due {
+            Some(due) => Reminder::new(reminder.at() + (date - due.date)),
+            None => Reminder::new(date.and_time(reminder.at().time())),
+        });
+        for subtask in next.subtasks.iter_mut() {
+            subtask.completed = false;
+        }
+        Some(next)
+    }
+
+    /// How the task's due date relates to `now`
+    ///
+    /// # Returns
+    ///
+    /// None if the task has no due date or has been completed
+    pub fn due_status(&self, now: NaiveDateTime) -> Option<DueStatus> {
+        match (self.completed, self.due) {
+            (false, Some(due)) => Some(due.status(now)),
+            (_, _) => None,
+        }
+    }
+}
+
+/// A set of tags, stored trimmed and in alphabetical order
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(transparent)]
+pub struct Tags(BTreeSet<String>);
+
+impl Tags {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a tag to the set, ignoring surrounding whitespace
+    ///
+    /// # Returns
+    ///
+    /// Whether the tag was added, false if it was empty or already in the set
+    pub fn insert(&mut self, tag: &str) -> bool {
+        let tag = tag.trim();
+        if tag.is_empty() {
+            return false;
+        }
+        self.0.insert(tag.to_string())
+    }
+
+    /// Removes a tag from the set, returning whether it was in the set
+    pub fn remove(&mut self, tag: &str) -> bool {
+        self.0.remove(tag.trim())
+    }
+
+    pub fn contains(&self, tag: &str) -> bool {
+        self.0.contains(tag.trim())
+    }
+
+    /// Whether any of the tags in `other` are in the set
+    pub fn intersects(&self, other: &Tags) -> bool {
+        !self.0.is_disjoint(&other.0)
+    }
+
+    pub fn iter(&self) -> std::collections::btree_set::Iter<'_, String> {
+        self.0.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Tags {
+    fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
+        let mut tags = Self::new();
+        for tag in iter {
+            tags.insert(tag);
+        }
+        tags
+    }
+}
+
+impl Extend<String> for Tags {
+    fn extend<T: IntoIterator<Item = String>>(&mut self, iter: T) {
+        for tag in iter {
+            self.insert(&tag);
+        }
+    }
+}
+
+/// A single item in a task's checklist
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+pub struct Subtask {
+    /// Whether the subtask has been completed
+    #[serde(default)]
+    completed: bool,
+    /// The title of the subtask
+    title: String,
+}
+
+impl Subtask {
+    pub fn new(title: String) -> Self {
+        Self {
+            completed: false,
+            title,
+        }
+    }
+
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+
+    pub fn set_completed(&mut self, completed: bool) {
+        self.completed = completed;
+    }
+
+    /// Gets a mutable reference to the completed field of the subtask
+    pub fn mut_completed(&mut self) -> &mut bool {
+        &mut self.completed
+    }
+
+    /// A clone of the subtasks title
+    pub fn title(&self) -> String {
+        self.title.clone()
+    }
+
+    pub fn set_title(&mut self, title: String) {
+        self.title = title;
+    }
+}
+
+/// How important a task is, ordered from least to most important
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    serde::Deserialize,
+    serde::Serialize,
+)]
+pub enum Priority {
+    #[default]
+    None,
+    Low,
+    Medium,
+    High,
+    Urgent,
+}
+
+impl Priority {
+    /// All the priorities, from least to most important
+    pub const ALL: [Priority; 5] = [
+        Priority::None,
+        Priority::Low,
+        Priority::Medium,
+        Priority::High,
+        Priority::Urgent,
+    ];
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Priority::None => "None",
+            Priority::Low => "Low",
+            Priority::Medium => "Medium",
+            Priority::High => "High",
+            Priority::Urgent => "Urgent",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The stage of work a task is at, which is the column it is shown in on a kanban board
+#[derive(
+    Clone, Copy, Debug, Default, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize,
+)]
+pub enum Status {
+    #[default]
+    Todo,
+    Doing,
+    Done,
+}
+
+impl Status {
+    /// All the statuses, in the order work moves through them
+    pub const ALL: [Status; 3] = [Status::Todo, Status::Doing, Status::Done];
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Status::Todo => "To Do",
+            Status::Doing => "Doing",
+            Status::Done => "Done",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The orders a list can be sorted in
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SortOrder {
+    CompletedFirst,
+    DueDate,
+    Priority,
+}
+
+impl SortOrder {
+    pub const ALL: [SortOrder; 3] = [
+        SortOrder::CompletedFirst,
+        SortOrder::DueDate,
+        SortOrder::Priority,
+    ];
+}
+
+impl std::fmt::Display for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SortOrder::CompletedFirst => "Completed first",
+            SortOrder::DueDate => "By due date",
+            SortOrder::Priority => "By priority",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The date, and optionally the time, that a task is due
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct DueDate {
+    /// The day the task is due
+    date: NaiveDate,
+    /// The time of day the task is due, if any
+    time: Option<NaiveTime>,
+}
+
+impl DueDate {
+    pub fn new(date: NaiveDate, time: Option<NaiveTime>) -> Self {
+        Self { date, time }
+    }
+
+    pub fn date(&self) -> NaiveDate {
+        self.date
+    }
+
+    pub fn time(&self) -> Option<NaiveTime> {
+        self.time
+    }
+
+    /// The key to order due dates by, treating due dates without a time as due at the end of the
+    /// day
+    fn sort_key(&self) -> (NaiveDate, bool, Option<NaiveTime>) {
+        (self.date, self.time.is_none(), self.time)
+    }
+
+    /// How the due date relates to `now`
+    pub fn status(&self, now: NaiveDateTime) -> DueStatus {
+        let overdue = match self.time {
+            Some(time) => self.date.and_time(time) < now,
+            None => self.date < now.date(),
+        };
+
+        if overdue {
+            DueStatus::Overdue
+        } else if self.date == now.date() {
+            DueStatus::DueToday
+        } else {
+            DueStatus::Upcoming
+        }
+    }
+}
+
+impl std::fmt::Display for DueDate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.time {
+            Some(time) => write!(f, "{} {}", self.date, time.format("%H:%M")),
+            None => write!(f, "{}", self.date),
+        }
+    }
+}
+
+/// How a due date relates to the current time
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DueStatus {
+    /// The due date has passed
+    Overdue,
+    /// The task is due later today
+    DueToday,
+    /// The task is due after today
+    Upcoming,
+}
+
+/// A time to remind the user about a task, which can be snoozed or dismissed
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Reminder {
+    /// When the reminder was set for
+    at: NaiveDateTime,
+    /// When the reminder was put off until, if it has been snoozed
+    #[serde(default)]
+    snoozed_until: Option<NaiveDateTime>,
+    /// Whether the user has dismissed the reminder
+    #[serde(default)]
+    dismissed: bool,
+}
+
+impl Reminder {
+    pub fn new(at: NaiveDateTime) -> Self {
+        Self {
+            at,
+            snoozed_until: None,
+            dismissed: false,
+        }
+    }
+
+    pub fn at(&self) -> NaiveDateTime {
+        self.at
+    }
+
+    /// When the reminder goes off, taking snoozing into account
+    pub fn goes_off_at(&self) -> NaiveDateTime {
+        self.snoozed_until.unwrap_or(self.at)
+    }
+
+    pub fn snoozed_until(&self) -> Option<NaiveDateTime> {
+        self.snoozed_until
+    }
+
+    pub fn dismissed(&self) -> bool {
+        self.dismissed
+    }
+
+    /// Whether the reminder has gone off and hasn't been dismissed
+    pub fn is_due(&self, now: NaiveDateTime) -> bool {
+        !self.dismissed && self.goes_off_at() <= now
+    }
+
+    /// Puts the reminder off until the given time
+    pub fn snooze(&mut self, until: NaiveDateTime) {
+        self.snoozed_until = Some(until);
+    }
+
+    /// Stops the reminder from going off
+    pub fn dismiss(&mut self) {
+        self.dismissed = true;
+    }
+}
+
+/// A stretch of time spent working on a task
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct TimeSession {
+    started_at: NaiveDateTime,
+    /// When the session ended, or None if the timer is still running
+    #[serde(default)]
+    stopped_at: Option<NaiveDateTime>,
+}
+
+impl TimeSession {
+    pub fn started_at(&self) -> NaiveDateTime {
+        self.started_at
+    }
+
+    pub fn stopped_at(&self) -> Option<NaiveDateTime> {
+        self.stopped_at
+    }
+
+    /// How long the session lasted, counting a running session up to `now`
+    pub fn duration(&self, now: NaiveDateTime) -> chrono::Duration {
+        (self.stopped_at.unwrap_or(now) - self.started_at).max(chrono::Duration::zero())
+    }
+}
+
+/// How often a task repeats
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum Recurrence {
+    Daily,
+    /// On the given days of the week, or a week after the due date if no days are given
+    Weekly(Vec<Weekday>),
+    /// On the same day of each month as the due date, or the last day of months that are too short
+    Monthly,
+    /// On the given day of each month, or the last day of months that are too short. Monthly
+    /// tasks become this once they repeat, so that they go back to their day after a short month
+    MonthlyOn(u32),
+    /// Every given number of days
+    EveryDays(u32),
+}
+
+impl Recurrence {
+    /// The first day the task is due on after the given date
+    pub fn next_after(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            Recurrence::Daily => date + chrono::Duration::days(1),
+            Recurrence::Weekly(days) if days.is_empty() => date + chrono::Duration::weeks(1),
+            Recurrence::Weekly(days) => (1..=7)
+                .map(|offset| date + chrono::Duration::days(offset))
+                .find(|next| days.contains(&next.weekday()))
+                .unwrap(),
+            Recurrence::Monthly => date
+                .checked_add_months(Months::new(1))
+                .unwrap_or(NaiveDate::MAX),
+            Recurrence::MonthlyOn(day) => {
+                let next = date
+                    .checked_add_months(Months::new(1))
+                    .unwrap_or(NaiveDate::MAX);
+                (1..=*day)
+                    .rev()
+                    .find_map(|day| next.with_day(day))
+                    .unwrap_or(next)
+            }
+            Recurrence::EveryDays(days) => date + chrono::Duration::days((*days).max(1) as i64),
+        }
+    }
+}
+
+impl std::fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Recurrence::Daily => write!(f, "Every day"),
+            Recurrence::Weekly(days) if days.is_empty() => write!(f, "Every week"),
+            Recurrence::Weekly(days) => {
+                let days: Vec<String> = days.iter().map(|day| day.to_string()).collect();
+                write!(f, "Every {}", days.join(", "))
+            }
+            Recurrence::Monthly | Recurrence::MonthlyOn(_) => write!(f, "Every month"),
+            Recurrence::EveryDays(days) => write!(f, "Every {} days", days),
+        }
+    }
+}
+
+/// How many days ahead a task counts as due soon
+pub const DUE_SOON_DAYS: i64 = 3;
+
+/// Which tasks in a list are shown, hiding the others without removing them
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum TaskFilter {
+    #[default]
+    All,
+    /// Tasks that haven't been completed
+    Open,
+    Completed,
+    /// Open tasks that are overdue or due within [`DUE_SOON_DAYS`] days
+    DueSoon,
+    /// Tasks with the given tag
+    Tagged(String),
+}
+
+impl TaskFilter {
+    /// Whether the filter shows the task
+    ///
+    /// # Arguments
+    ///
+    /// * `task` - The task to check
+    /// * `now`  - The current time, used to work out which tasks are due soon
+    pub fn matches(&self, task: &Task, now: NaiveDateTime) -> bool {
+        match self {
+            TaskFilter::All => true,
+            TaskFilter::Open => !task.completed,
+            TaskFilter::Completed => task.completed,
+            TaskFilter::DueSoon => task.due.is_some_and(|due| {
+                !task.completed && due.date <= now.date() + chrono::Duration::days(DUE_SOON_DAYS)
+            }),
+            TaskFilter::Tagged(tag) => task.tags.contains(tag),
+        }
+    }
+}
+
+impl std::fmt::Display for TaskFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TaskFilter::All => write!(f, "All"),
+            TaskFilter::Open => write!(f, "Open"),
+            TaskFilter::Completed => write!(f, "Completed"),
+            TaskFilter::DueSoon => write!(f, "Due soon"),
+            TaskFilter::Tagged(tag) => write!(f, "#{}", tag),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn task(title: &str, completed: bool) -> Task {
+        Task::builder()
+            .title(title.to_string())
+            .completed(completed)
+            .build()
+            .unwrap()
+    }
+
     #[test]
     fn builder_works() {
         let task = Task::builder()
@@ -95,4 +1100,390 @@ mod tests {
         assert!(!task.completed);
         assert_eq!(task.description, String::new());
     }
+
+    #[test]
+    fn duplicate_task_ids_are_replaced() {
+        let mut list = List::new("List".to_string());
+        let task = task("One", false);
+        list.add(task.clone());
+        list.add(task.clone());
+
+        assert_eq!(list.get(0).unwrap().id(), task.id());
+        assert_ne!(list.get(1).unwrap().id(), task.id());
+    }
+
+    #[test]
+    fn progress_of_empty_list_is_zero() {
+        let list = List::new("Empty".to_string());
+        assert_eq!(list.progress(), 0.0);
+    }
+
+    #[test]
+    fn sort_completed_first_is_stable() {
+        let mut list = List::new("List".to_string());
+        list.add(task("One", false));
+        list.add(task("Two", true));
+        list.add(task("Three", false));
+        list.add(task("Four", true));
+        list.sort_completed_first();
+
+        let titles: Vec<String> = list.iter().map(|task| task.title()).collect();
+        assert_eq!(titles, vec!["Two", "Four", "One", "Three"]);
+    }
+
+    fn date(day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(2023, 6, day).unwrap()
+    }
+
+    fn time(hour: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn due_status_works() {
+        let now = date(10).and_time(time(12));
+        let status = |due: DueDate| due.status(now);
+
+        assert_eq!(status(DueDate::new(date(9), None)), DueStatus::Overdue);
+        assert_eq!(
+            status(DueDate::new(date(10), Some(time(11)))),
+            DueStatus::Overdue
+        );
+        assert_eq!(status(DueDate::new(date(10), None)), DueStatus::DueToday);
+        assert_eq!(
+            status(DueDate::new(date(10), Some(time(13)))),
+            DueStatus::DueToday
+        );
+        assert_eq!(status(DueDate::new(date(11), None)), DueStatus::Upcoming);
+    }
+
+    #[test]
+    fn status_follows_completion() {
+        let mut task = Task::builder()
+            .title("Task".to_string())
+            .status(Status::Doing)
+            .build()
+            .unwrap();
+        assert_eq!(task.status(), Status::Doing);
+
+        task.set_completed(true);
+        assert_eq!(task.status(), Status::Done);
+        task.set_completed(false);
+        assert_eq!(task.status(), Status::Doing);
+
+        task.set_status(Status::Done);
+        assert!(task.completed());
+        task.set_status(Status::Todo);
+        assert!(!task.completed());
+        assert_eq!(task.status(), Status::Todo);
+    }
+
+    #[test]
+    fn filters_hide_tasks() {
+        let now = date(10).and_time(time(12));
+        let mut tagged = task("Tagged", false);
+        tagged.tags_mut().insert("work");
+        let soon = Task::builder()
+            .title("Soon".to_string())
+            .due(DueDate::new(date(12), None))
+            .build()
+            .unwrap();
+        let later = Task::builder()
+            .title("Later".to_string())
+            .due(DueDate::new(date(20), None))
+            .build()
+            .unwrap();
+        let done = task("Done", true);
+
+        let shown = |filter: TaskFilter| -> Vec<String> {
+            [&tagged, &soon, &later, &done]
+                .into_iter()
+                .filter(|task| filter.matches(task, now))
+                .map(|task| task.title())
+                .collect()
+        };
+        assert_eq!(shown(TaskFilter::All).len(), 4);
+        assert_eq!(shown(TaskFilter::Open), vec!["Tagged", "Soon", "Later"]);
+        assert_eq!(shown(TaskFilter::Completed), vec!["Done"]);
+        assert_eq!(shown(TaskFilter::DueSoon), vec!["Soon"]);
+        assert_eq!(
+            shown(TaskFilter::Tagged("work".to_string())),
+            vec!["Tagged"]
+        );
+    }
+
+    #[test]
+    fn timestamps_follow_changes() {
+        let created = date(1).and_time(time(9));
+        let mut task = Task::builder()
+            .title("Task".to_string())
+            .created_at(created)
+            .build()
+            .unwrap();
+
+        let previous = task.clone();
+        task.update_timestamps(&previous, date(2).and_time(time(9)));
+        assert_eq!(task.modified_at(), None);
+
+        let previous = task.clone();
+        task.set_completed(true);
+        task.update_timestamps(&previous, date(3).and_time(time(9)));
+        assert_eq!(task.completed_at(), Some(date(3).and_time(time(9))));
+        assert_eq!(task.modified_at(), task.completed_at());
+        assert_eq!(task.time_to_complete(), Some(chrono::Duration::days(2)));
+
+        let previous = task.clone();
+        task.set_completed(false);
+        task.update_timestamps(&previous, date(4).and_time(time(9)));
+        assert_eq!(task.completed_at(), None);
+        assert_eq!(task.time_to_complete(), None);
+        assert_eq!(task.modified_at(), Some(date(4).and_time(time(9))));
+    }
+
+    #[test]
+    fn recurrences_find_the_next_date() {
+        // The 10th of June 2023 is a Saturday
+        assert_eq!(Recurrence::Daily.next_after(date(10)), date(11));
+        let weekdays = Recurrence::Weekly(vec![Weekday::Mon, Weekday::Fri]);
+        assert_eq!(weekdays.next_after(date(10)), date(12));
+        assert_eq!(weekdays.next_after(date(12)), date(16));
+        assert_eq!(Recurrence::Weekly(vec![]).next_after(date(10)), date(17));
+        assert_eq!(Recurrence::EveryDays(3).next_after(date(10)), date(13));
+
+        let end_of_month = NaiveDate::from_ymd_opt(2023, 1, 31).unwrap();
+        assert_eq!(
+            Recurrence::Monthly.next_after(end_of_month),
+            NaiveDate::from_ymd_opt(2023, 2, 28).unwrap()
+        );
+        assert_eq!(
+            Recurrence::MonthlyOn(31).next_after(NaiveDate::from_ymd_opt(2023, 2, 28).unwrap()),
+            NaiveDate::from_ymd_opt(2023, 3, 31).unwrap()
+        );
+    }
+
+    #[test]
+    fn reminders_without_a_due_date_go_off_on_the_next_occurrence() {
+        let mut reminder = Reminder::new(date(2).and_time(time(9)));
+        reminder.snooze(date(2).and_time(time(10)));
+        reminder.dismiss();
+        let water = Task::builder()
+            .title("Water the plants".to_string())
+            .recurrence(Recurrence::EveryDays(3))
+            .reminder(reminder)
+            .build()
+            .unwrap();
+
+        let next = water.next_occurrence(date(10).and_time(time(12))).unwrap();
+        assert_eq!(next.due(), Some(DueDate::new(date(13), None)));
+        assert_eq!(
+            next.reminder(),
+            Some(Reminder::new(date(13).and_time(time(9))))
+        );
+        assert!(!next.reminder().unwrap().is_due(date(12).and_time(time(9))));
+    }
+
+    #[test]
+    fn monthly_tasks_keep_their_day_after_short_months() {
+        let day = |month, day| NaiveDate::from_ymd_opt(2023, month, day).unwrap();
+        let mut rent = Task::builder()
+            .title("Pay rent".to_string())
+            .due(DueDate::new(day(1, 31), None))
+            .recurrence(Recurrence::Monthly)
+            .build()
+            .unwrap();
+
+        let mut dues = vec![];
+        for _ in 0..3 {
+            rent = rent.next_occurrence(day(1, 31).and_time(time(9))).unwrap();
+            dues.push(rent.due().unwrap().date);
+        }
+        assert_eq!(dues, vec![day(2, 28), day(3, 31), day(4, 30)]);
+        assert_eq!(rent.recurrence(), Some(&Recurrence::MonthlyOn(31)));
+    }
+
+    #[test]
+    fn completing_a_recurring_task_schedules_the_next_one() {
+        let mut list = List::new("Chores".to_string());
+        let mut report = Task::builder()
+            .title("Weekly report".to_string())
+            .due(DueDate::new(date(2), Some(time(17))))
+            .recurrence(Recurrence::Weekly(vec![]))
+            .reminder(Reminder::new(date(2).and_time(time(9))))
+            .subtasks(vec![Subtask::new("Collect numbers".to_string())])
+            .build()
+            .unwrap();
+        report.subtask_mut(0).unwrap().set_completed(true);
+        list.add(report);
+        list.add(task("Other", false));
+        assert_eq!(list.schedule_recurrences(date(10).and_time(time(9))), 0);
+
+        list.get_mut(0).unwrap().set_completed(true);
+        assert_eq!(list.schedule_recurrences(date(10).and_time(time(9))), 1);
+        assert_eq!(list.schedule_recurrences(date(10).and_time(time(9))), 0);
+
+        let next = list.get(1).unwrap();
+        assert_eq!(next.title(), "Weekly report");
+        assert!(!next.completed());
+        assert!(!next.subtasks()[0].completed());
+        // The occurrence on the 9th was missed, so the next is a week later
+        assert_eq!(next.due(), Some(DueDate::new(date(16), Some(time(17)))));
+        assert!(list.get(0).unwrap().recurrence().is_none());
+        assert_eq!(
+            next.reminder(),
+            Some(Reminder::new(date(16).and_time(time(9))))
+        );
+        assert_ne!(list.get(0).unwrap().id(), next.id());
+    }
+
+    #[test]
+    fn reminders_can_be_snoozed_and_dismissed() {
+        let mut task = Task::builder()
+            .title("Task".to_string())
+            .reminder(Reminder::new(date(10).and_time(time(9))))
+            .build()
+            .unwrap();
+        assert!(!task.reminder_due(date(10).and_time(time(8))));
+        assert!(task.reminder_due(date(10).and_time(time(9))));
+
+        task.reminder_mut()
+            .unwrap()
+            .snooze(date(10).and_time(time(10)));
+        assert!(!task.reminder_due(date(10).and_time(time(9))));
+        assert!(task.reminder_due(date(10).and_time(time(11))));
+
+        task.reminder_mut().unwrap().dismiss();
+        assert!(!task.reminder_due(date(11).and_time(time(9))));
+
+        let mut task = Task::builder()
+            .title("Task".to_string())
+            .completed(true)
+            .reminder(Reminder::new(date(10).and_time(time(9))))
+            .build()
+            .unwrap();
+        assert!(!task.reminder_due(date(10).and_time(time(9))));
+        task.set_completed(false);
+        assert!(task.reminder_due(date(10).and_time(time(9))));
+    }
+
+    #[test]
+    fn timers_log_sessions_until_the_task_is_completed() {
+        let mut task = task("Task", false);
+        let previous = task.clone();
+        task.start_timer(date(10).and_time(time(9)));
+        task.start_timer(date(10).and_time(time(10)));
+        assert_eq!(task.timer_started_at(), Some(date(10).and_time(time(9))));
+        assert_eq!(
+            task.time_spent(date(10).and_time(time(11))),
+            chrono::Duration::hours(2)
+        );
+        task.update_timestamps(&previous, date(10).and_time(time(11)));
+
+        task.stop_timer(date(10).and_time(time(11)));
+        task.start_timer(date(10).and_time(time(13)));
+        assert_eq!(task.time_log().len(), 2);
+
+        let previous = task.clone();
+        task.set_completed(true);
+        task.update_timestamps(&previous, date(10).and_time(time(14)));
+        assert_eq!(task.timer_started_at(), None);
+        assert_eq!(
+            task.time_spent(date(11).and_time(time(9))),
+            chrono::Duration::hours(3)
+        );
+    }
+
+    #[test]
+    fn completed_tasks_have_no_due_status() {
+        let task = Task::builder()
+            .title("Task".to_string())
+            .completed(true)
+            .due(DueDate::new(date(1), None))
+            .build()
+            .unwrap();
+        assert_eq!(task.due_status(date(10).and_time(time(12))), None);
+    }
+
+    #[test]
+    fn sort_by_due_date_works() {
+        let due_task = |title: &str, due: DueDate| {
+            Task::builder()
+                .title(title.to_string())
+                .due(due)
+                .build()
+                .unwrap()
+        };
+        let mut list = List::new("List".to_string());
+        list.add(task("None", false));
+        list.add(due_task("Later", DueDate::new(date(12), None)));
+        list.add(due_task("Evening", DueDate::new(date(10), None)));
+        list.add(due_task("Morning", DueDate::new(date(10), Some(time(9)))));
+        list.sort_by_due_date();
+
+        let titles: Vec<String> = list.iter().map(|task| task.title()).collect();
+        assert_eq!(titles, vec!["Morning", "Evening", "Later", "None"]);
+    }
+
+    #[test]
+    fn sort_by_priority_works() {
+        let priority_task = |title: &str, priority: Priority, completed: bool| {
+            Task::builder()
+                .title(title.to_string())
+                .priority(priority)
+                .completed(completed)
+                .build()
+                .unwrap()
+        };
+        let mut list = List::new("List".to_string());
+        list.add(priority_task("Low", Priority::Low, false));
+        list.add(priority_task("Urgent done", Priority::Urgent, true));
+        list.add(priority_task("None", Priority::None, false));
+        list.add(priority_task("Urgent", Priority::Urgent, false));
+        list.sort_by_priority();
+
+        let titles: Vec<String> = list.iter().map(|task| task.title()).collect();
+        assert_eq!(titles, vec!["Urgent", "Urgent done", "Low", "None"]);
+    }
+
+    #[test]
+    fn progress_counts_subtasks() {
+        let mut with_subtasks = task("Subtasks", false);
+        with_subtasks.add_subtask(Subtask::new("One".to_string()));
+        with_subtasks.add_subtask(Subtask::new("Two".to_string()));
+        with_subtasks.subtask_mut(0).unwrap().set_completed(true);
+
+        let mut list = List::new("List".to_string());
+        list.add(with_subtasks);
+        list.add(task("Done", true));
+
+        assert_eq!(list.progress(), 0.5);
+        assert_eq!(list.progress_with_subtasks(), 0.75);
+    }
+
+    #[test]
+    fn tags_are_trimmed_and_unique() {
+        let mut tags = Tags::new();
+        assert!(tags.insert(" work "));
+        assert!(!tags.insert("work"));
+        assert!(!tags.insert("   "));
+        assert!(tags.contains("work"));
+        assert_eq!(tags.len(), 1);
+
+        let active: Tags = ["home", "work"].into_iter().collect();
+        assert!(tags.intersects(&active));
+        assert!(tags.remove("work"));
+        assert!(!tags.intersects(&active));
+    }
+
+    #[test]
+    fn move_task_works() {
+        let mut list = List::new("List".to_string());
+        list.add(task("One", false));
+        list.add(task("Two", false));
+        list.add(task("Three", false));
+
+        assert!(list.move_task(0, 2));
+        assert!(!list.move_task(5, 0));
+        let titles: Vec<String> = list.iter().map(|task| task.title()).collect();
+        assert_eq!(titles, vec!["Two", "Three", "One"]);
+    }
 }
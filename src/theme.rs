@@ -0,0 +1,290 @@
+//! This module contains the colour themes the app can be shown in
+//!
+//! Along with the built in light and dark themes, users can define their own themes in
+//! `themes.json` in the app's config directory. Colours are written as hex, and a theme can be
+//! built on either the light or dark theme:
+//!
+//! ```json
+//! {
+//!   "themes": [
+//!     {
+//!       "name": "Solarized",
+//!       "dark": true,
+//!       "border": "#586e75",
+//!       "background": "#002b36",
+//!       "outline": "#93a1a1",
+//!       "window": "#073642",
+//!       "text": "#eee8d5"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! `border` is the colour around each widget, `background` the colour inside it and `outline` is
+//! used for drop indicators and faint text. `window` and `text` can be left out to keep the
+//! colours of the theme it is built on.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// The name of the themes file in the app's config directory
+const THEMES_FILE_NAME: &str = "themes.json";
+
+/// A colour without transparency, written as hex such as `#1e90ff`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Colour {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+impl Colour {
+    pub const fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b }
+    }
+
+    pub const fn gray(level: u8) -> Self {
+        Self::new(level, level, level)
+    }
+}
+
+impl TryFrom<String> for Colour {
+    type Error = String;
+
+    fn try_from(hex: String) -> Result<Self, Self::Error> {
+        let digits = hex.trim().trim_start_matches('#');
+        let channel = |index: usize| {
+            digits
+                .get(index..index + 2)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+        };
+        match (digits.len(), channel(0), channel(2), channel(4)) {
+            (6, Some(r), Some(g), Some(b)) => Ok(Self::new(r, g, b)),
+            _ => Err(format!("\"{}\" isn't a colour like #1e90ff", hex)),
+        }
+    }
+}
+
+impl From<Colour> for String {
+    fn from(colour: Colour) -> Self {
+        colour.to_string()
+    }
+}
+
+impl fmt::Display for Colour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+/// The colours the app is drawn in
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Theme {
+    pub name: String,
+    /// Whether the theme is built on the dark theme rather than the light one
+    #[serde(default)]
+    pub dark: bool,
+    /// The colour around each widget
+    pub border: Colour,
+    /// The colour inside each widget
+    pub background: Colour,
+    /// The colour of drop indicators and faint text
+    pub outline: Colour,
+    /// The colour behind the widgets, the colour of the theme it is built on when None
+    #[serde(default)]
+    pub window: Option<Colour>,
+    /// The colour of text, the colour of the theme it is built on when None
+    #[serde(default)]
+    pub text: Option<Colour>,
+}
+
+impl Theme {
+    pub fn light() -> Self {
+        Self {
+            name: "Light".to_string(),
+            dark: false,
+            border: Colour::gray(160),
+            background: Colour::gray(255),
+            outline: Colour::gray(96),
+            window: None,
+            text: None,
+        }
+    }
+
+    pub fn dark() -> Self {
+        Self {
+            name: "Dark".to_string(),
+            dark: true,
+            border: Colour::gray(70),
+            background: Colour::gray(40),
+            outline: Colour::gray(160),
+            window: None,
+            text: None,
+        }
+    }
+}
+
+/// Enum to model the theme the user picked
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub enum ThemeChoice {
+    #[default]
+    Light,
+    Dark,
+    /// Light or dark, whichever the desktop is using, or light if the desktop's theme isn't known
+    System,
+    /// One of the user's themes, by name
+    Custom(String),
+}
+
+impl ThemeChoice {
+    /// The choices that are always available, the user's themes come after these
+    pub const BUILT_IN: [ThemeChoice; 3] =
+        [ThemeChoice::Light, ThemeChoice::Dark, ThemeChoice::System];
+}
+
+impl fmt::Display for ThemeChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ThemeChoice::Light => write!(f, "Light"),
+            ThemeChoice::Dark => write!(f, "Dark"),
+            ThemeChoice::System => write!(f, "Follow system"),
+            ThemeChoice::Custom(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Finds the theme to draw the app in
+///
+/// # Arguments
+///
+/// * `choice`      - The theme the user picked
+/// * `themes`      - The user's themes
+/// * `system_dark` - Whether the desktop is using a dark theme, or None if it isn't known
+///
+/// # Returns
+///
+/// The theme picked, falling back to the light theme if the desktop's theme isn't known or the
+/// user's theme no longer exists
+pub fn resolve(choice: &ThemeChoice, themes: &[Theme], system_dark: Option<bool>) -> Theme {
+    match choice {
+        ThemeChoice::Light => Theme::light(),
+        ThemeChoice::Dark => Theme::dark(),
+        ThemeChoice::System => match system_dark {
+            Some(true) => Theme::dark(),
+            _ => Theme::light(),
+        },
+        ThemeChoice::Custom(name) => themes
+            .iter()
+            .find(|theme| &theme.name == name)
+            .cloned()
+            .unwrap_or_else(Theme::light),
+    }
+}
+
+/// The contents of the themes file
+#[derive(serde::Deserialize)]
+struct ThemesFile {
+    themes: Vec<Theme>,
+}
+
+/// The path the user's themes are loaded from
+///
+/// # Returns
+///
+/// `themes.json` in the app's config directory, or None if there is no config directory
+pub fn themes_path() -> Option<PathBuf> {
+    directories_next::ProjectDirs::from("", "", "rusty_taskboard")
+        .map(|dirs| dirs.config_dir().join(THEMES_FILE_NAME))
+}
+
+/// Loads the user's themes from a file
+///
+/// # Arguments
+///
+/// * `path` - The path of the themes file
+///
+/// # Returns
+///
+/// The themes in the file, or an error if the file couldn't be read or isn't a valid themes file
+pub fn load(path: &Path) -> io::Result<Vec<Theme>> {
+    let contents = fs::read_to_string(path)?;
+    let file: ThemesFile = serde_json::from_str(&contents)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+    Ok(file.themes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colours_are_read_and_written_as_hex() {
+        let colour = Colour::try_from("#1E90ff".to_string()).unwrap();
+        assert_eq!(colour, Colour::new(30, 144, 255));
+        assert_eq!(colour.to_string(), "#1e90ff");
+        assert_eq!(Colour::try_from("1e90ff".to_string()), Ok(colour));
+
+        assert!(Colour::try_from("#1e90f".to_string()).is_err());
+        assert!(Colour::try_from("#1e90fg".to_string()).is_err());
+        assert!(Colour::try_from("#1e90ff00".to_string()).is_err());
+    }
+
+    #[test]
+    fn choices_fall_back_to_the_light_theme() {
+        let mut solarized = Theme::dark();
+        solarized.name = "Solarized".to_string();
+        let themes = [solarized.clone()];
+
+        assert_eq!(resolve(&ThemeChoice::Dark, &themes, None), Theme::dark());
+        assert_eq!(
+            resolve(&ThemeChoice::System, &themes, Some(true)),
+            Theme::dark()
+        );
+        assert_eq!(
+            resolve(&ThemeChoice::System, &themes, Some(false)),
+            Theme::light()
+        );
+        assert_eq!(resolve(&ThemeChoice::System, &themes, None), Theme::light());
+
+        let custom = ThemeChoice::Custom("Solarized".to_string());
+        assert_eq!(resolve(&custom, &themes, None), solarized);
+        assert_eq!(resolve(&custom, &[], None), Theme::light());
+    }
+
+    #[test]
+    fn hand_written_files_load() {
+        let path = std::env::temp_dir()
+            .join(format!("rusty_taskboard_{}", uuid::Uuid::new_v4()))
+            .join(THEMES_FILE_NAME);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let contents = r##"{
+            "themes": [{
+                "name": "Solarized",
+                "dark": true,
+                "border": "#586e75",
+                "background": "#002b36",
+                "outline": "#93a1a1",
+                "text": "#eee8d5"
+            }]
+        }"##;
+        fs::write(&path, contents).unwrap();
+
+        let themes = load(&path).unwrap();
+        assert_eq!(themes[0].name, "Solarized");
+        assert_eq!(themes[0].background, Colour::new(0, 43, 54));
+        assert_eq!(themes[0].window, None);
+        assert_eq!(themes[0].text, Some(Colour::new(238, 232, 213)));
+
+        fs::write(
+            &path,
+            r##"{ "themes": [{ "name": "Bad", "border": "red" }] }"##,
+        )
+        .unwrap();
+        assert_eq!(load(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+}
@@ -0,0 +1,136 @@
+//! This module contains exporting the time spent on tasks as CSV, for filling in timesheets
+//!
+//! Each row is one session of work on a task, including the tasks in the archive. A session
+//! that is still running has an empty `stopped_at` and is counted up to the time of the export
+//!
+//! ```csv
+//! list,task,started_at,stopped_at,minutes
+//! Work,"Review pull requests, docs",2023-06-05 09:00,2023-06-05 10:30,90
+//! ```
+
+use chrono::NaiveDateTime;
+
+use crate::board::Board;
+use crate::task::Task;
+
+/// The columns of the exported CSV
+const HEADER: &str = "list,task,started_at,stopped_at,minutes";
+/// The format the start and stop times are written in
+const TIME_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Exports the time log of every task on a board, oldest session first
+///
+/// # Arguments
+///
+/// * `board` - The board to export the time log of
+/// * `now`   - The current time, which running sessions are counted up to
+///
+/// # Returns
+///
+/// The time log as CSV, with a header row
+pub fn export_csv(board: &Board, now: NaiveDateTime) -> String {
+    let tasks = board
+        .lists()
+        .iter()
+        .flat_map(|list| list.iter().map(move |task| (list.name(), task)))
+        .chain(
+            board
+                .archive()
+                .iter()
+                .map(|archived| (archived.list_name(), archived.task())),
+        );
+
+    let mut rows: Vec<(NaiveDateTime, String)> = tasks
+        .flat_map(|(list_name, task)| rows(&list_name, task, now))
+        .collect();
+    rows.sort_by_key(|(started_at, _)| *started_at);
+
+    let mut csv = HEADER.to_string();
+    for (_, row) in rows {
+        csv.push('\n');
+        csv.push_str(&row);
+    }
+    csv.push('\n');
+    csv
+}
+
+/// Builds the rows for each session in the time log of a task, along with when it started
+fn rows(list_name: &str, task: &Task, now: NaiveDateTime) -> Vec<(NaiveDateTime, String)> {
+    task.time_log()
+        .iter()
+        .map(|session| {
+            let stopped_at = session
+                .stopped_at()
+                .map(|stopped_at| stopped_at.format(TIME_FORMAT).to_string())
+                .unwrap_or_default();
+            let row = [
+                escape(list_name),
+                escape(&task.title()),
+                session.started_at().format(TIME_FORMAT).to_string(),
+                stopped_at,
+                session.duration(now).num_minutes().to_string(),
+            ]
+            .join(",");
+            (session.started_at(), row)
+        })
+        .collect()
+}
+
+/// Quotes a field if it contains a comma, quote or line break, doubling any quotes inside it
+fn escape(field: &str) -> String {
+    match field.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::NaiveDate;
+
+    fn at(hour: u32, minute: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 6, 5)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn sessions_are_exported_oldest_first() {
+        let mut review = Task::builder()
+            .title("Review \"the\" pull requests, docs".to_string())
+            .build()
+            .unwrap();
+        review.start_timer(at(9, 0));
+        review.stop_timer(at(10, 30));
+        review.start_timer(at(14, 0));
+        let mut plan = Task::builder().title("Plan".to_string()).build().unwrap();
+        plan.start_timer(at(11, 0));
+        plan.stop_timer(at(11, 45));
+
+        let mut board = Board::new("Board".to_string());
+        let list = board.create_list("Work".to_string());
+        list.add(review);
+        list.add(plan);
+        list.add(
+            Task::builder()
+                .title("Untimed".to_string())
+                .build()
+                .unwrap(),
+        );
+
+        assert_eq!(
+            export_csv(&board, at(14, 20)),
+            "list,task,started_at,stopped_at,minutes\n\
+             Work,\"Review \"\"the\"\" pull requests, docs\",2023-06-05 09:00,2023-06-05 10:30,90\n\
+             Work,Plan,2023-06-05 11:00,2023-06-05 11:45,45\n\
+             Work,\"Review \"\"the\"\" pull requests, docs\",2023-06-05 14:00,,20\n"
+        );
+        assert_eq!(
+            export_csv(&Board::new("Empty".to_string()), at(9, 0)),
+            format!("{}\n", HEADER)
+        );
+    }
+}
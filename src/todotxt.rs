@@ -0,0 +1,371 @@
+//! This module contains the import and export of boards in the todo.txt format
+//!
+//! Each task is a line, for example:
+//!
+//! ```text
+//! x 2023-06-11 2023-06-01 Write the release notes +Work @laptop due:2023-06-10 pri:B
+//! (A) 2023-06-02 Tag the release +Work @laptop
+//! ```
+//!
+//! The fields are mapped onto tasks as follows:
+//!
+//! * `x` marks the task as completed, followed by the completion date and then the creation date
+//! * `(A)` to `(D)` are the urgent, high, medium and low priorities, with later letters also being
+//!   low. Completed tasks keep their priority as `pri:A`, as the todo.txt format drops it
+//! * The first `+project` is the name of the list the task is in, with underscores for spaces
+//! * Each `@context` is a tag on the task
+//! * `due:YYYY-MM-DD` is the date the task is due, or `due:YYYY-MM-DDTHH:MM` if it is due at a
+//!   time
+//!
+//! Words of a title that would be read as one of these fields, such as `+word`, `@word` or a
+//! leading `x`, `(A)` or date, are escaped with a backslash, as is a word that starts with one.
+//!
+//! Descriptions and subtasks have no place in todo.txt, so they aren't exported.
+
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+use crate::board::Board;
+use crate::task::{DueDate, List, Priority, Task};
+
+/// The name of the list tasks without a project are put in
+const UNNAMED_LIST: &str = "Todo";
+
+/// The format of dates in todo.txt
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// The format of due dates that have a time
+const DUE_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M";
+
+/// The prefix that keeps a word of a title from being read as a field
+const ESCAPE: char = '\\';
+
+/// Exports the lists on a board to todo.txt, one task per line
+pub fn export(board: &Board) -> String {
+    let mut todo_txt = String::new();
+    for list in board.lists() {
+        for task in list.iter() {
+            todo_txt.push_str(&export_task(list, task));
+            todo_txt.push('\n');
+        }
+    }
+    todo_txt
+}
+
+/// Exports a task in a list as a todo.txt line
+fn export_task(list: &List, task: &Task) -> String {
+    let mut fields = vec![];
+
+    if task.completed() {
+        fields.push("x".to_string());
+        // The creation date can only be given after the completion date
+        if let Some(completed_at) = task.completed_at() {
+            fields.push(format_date(completed_at));
+            fields.extend(task.created_at().map(format_date));
+        }
+    } else {
+        fields.extend(priority_letter(task.priority()).map(|letter| format!("({})", letter)));
+        fields.extend(task.created_at().map(format_date));
+    }
+
+    fields.extend(
+        task.title()
+            .split_whitespace()
+            .enumerate()
+            .map(|(index, word)| escape_word(word, index == 0)),
+    );
+    fields.push(format!(
+        "+{}",
+        list.name()
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join("_")
+    ));
+    fields.extend(
+        task.tags()
+            .iter()
+            .map(|tag| format!("@{}", tag.replace(' ', "_"))),
+    );
+    if let Some(due) = task.due() {
+        let due = match due.time() {
+            Some(time) => due.date().and_time(time).format(DUE_TIME_FORMAT),
+            None => due.date().format(DATE_FORMAT),
+        };
+        fields.push(format!("due:{}", due));
+    }
+    if let (true, Some(letter)) = (task.completed(), priority_letter(task.priority())) {
+        fields.push(format!("pri:{}", letter));
+    }
+
+    fields.join(" ")
+}
+
+/// Imports the tasks in a todo.txt file
+///
+/// # Arguments
+///
+/// * `todo_txt` - The contents of the file
+///
+/// # Returns
+///
+/// A board with a list for every project, in the order they first appear. Tasks without a project
+/// are put in a list called "Todo"
+pub fn import(todo_txt: &str) -> Board {
+    let mut board = Board::default();
+
+    for line in todo_txt.lines() {
+        let Some((project, task)) = import_task(line) else {
+            continue;
+        };
+        let name = project.unwrap_or_else(|| UNNAMED_LIST.to_string());
+        match board.find_list_mut(&name) {
+            Some(list) => list.add(task),
+            None => board.create_list(name).add(task),
+        }
+    }
+
+    board
+}
+
+/// Imports a todo.txt line
+///
+/// # Returns
+///
+/// The name of the task's list if it has a project, and the task, or None if the line has no task
+fn import_task(line: &str) -> Option<(Option<String>, Task)> {
+    let mut tokens = line.split_whitespace().peekable();
+    let mut builder = Task::builder();
+    let mut completed = false;
+    let mut priority = Priority::None;
+
+    if tokens.peek() == Some(&"x") {
+        tokens.next();
+        completed = true;
+        // A lone date after the x is the completion date, and a second date is the creation date
+        let first_date = tokens.peek().and_then(|token| parse_date(token));
+        if let Some(date) = first_date {
+            tokens.next();
+            match tokens.peek().and_then(|token| parse_date(token)) {
+                Some(created_at) => {
+                    tokens.next();
+                    builder.completed_at(date).created_at(created_at);
+                }
+                None => {
+                    builder.completed_at(date);
+                }
+            }
+        }
+    } else {
+        if let Some(letter) = tokens.peek().and_then(|token| parse_priority(token)) {
+            tokens.next();
+            priority = letter;
+        }
+        if let Some(created_at) = tokens.peek().and_then(|token| parse_date(token)) {
+            tokens.next();
+            builder.created_at(created_at);
+        }
+    }
+
+    let mut project = None;
+    let mut title = vec![];
+    let mut tags = vec![];
+    for token in tokens {
+        if let Some(word) = token.strip_prefix(ESCAPE) {
+            title.push(word);
+            continue;
+        }
+        if let (None, Some(name)) = (&project, token.strip_prefix('+')) {
+            if !name.is_empty() {
+                project = Some(name.replace('_', " "));
+                continue;
+            }
+        }
+        if let Some(tag) = token.strip_prefix('@').filter(|tag| !tag.is_empty()) {
+            tags.push(tag);
+            continue;
+        }
+        if let Some(due) = token.strip_prefix("due:").and_then(parse_due) {
+            builder.due(due);
+            continue;
+        }
+        if let Some(letter) = token
+            .strip_prefix("pri:")
+            .and_then(|letter| parse_priority(&format!("({})", letter)))
+        {
+            priority = letter;
+            continue;
+        }
+        title.push(token);
+    }
+
+    if title.is_empty() {
+        return None;
+    }
+
+    let mut task = builder
+        .title(title.join(" "))
+        .completed(completed)
+        .priority(priority)
+        .build()
+        .ok()?;
+    for tag in tags {
+        task.tags_mut().insert(tag);
+    }
+    Some((project, task))
+}
+
+/// Escapes a word of a task's title if it would be read as a field rather than as part of the
+/// title
+///
+/// # Arguments
+///
+/// * `word`  - The word to escape
+/// * `first` - Whether the word starts the title, where priorities and dates are also read
+fn escape_word(word: &str, first: bool) -> String {
+    let field = word.starts_with(['+', '@', ESCAPE])
+        || word.starts_with("due:")
+        || word.starts_with("pri:");
+    let leading =
+        first && (word == "x" || parse_priority(word).is_some() || parse_date(word).is_some());
+    match field || leading {
+        true => format!("{}{}", ESCAPE, word),
+        false => word.to_string(),
+    }
+}
+
+/// The todo.txt priority letter for a priority, or None if it has no letter
+fn priority_letter(priority: Priority) -> Option<char> {
+    match priority {
+        Priority::None => None,
+        Priority::Low => Some('D'),
+        Priority::Medium => Some('C'),
+        Priority::High => Some('B'),
+        Priority::Urgent => Some('A'),
+    }
+}
+
+/// Parses a todo.txt priority such as `(A)`
+fn parse_priority(token: &str) -> Option<Priority> {
+    let letter = token.strip_prefix('(')?.strip_suffix(')')?;
+    let mut chars = letter.chars();
+    let (Some(letter), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    match letter {
+        'A' => Some(Priority::Urgent),
+        'B' => Some(Priority::High),
+        'C' => Some(Priority::Medium),
+        'D'..='Z' => Some(Priority::Low),
+        _ => None,
+    }
+}
+
+/// Parses a todo.txt date, which has no time, as the start of the day
+fn parse_date(token: &str) -> Option<NaiveDateTime> {
+    NaiveDate::parse_from_str(token, DATE_FORMAT)
+        .ok()
+        .map(|date| date.and_time(NaiveTime::MIN))
+}
+
+/// Parses a todo.txt due date, which may have a time such as `2023-06-10T17:00`
+fn parse_due(token: &str) -> Option<DueDate> {
+    match NaiveDateTime::parse_from_str(token, DUE_TIME_FORMAT) {
+        Ok(due) => Some(DueDate::new(due.date(), Some(due.time()))),
+        Err(_) => parse_date(token).map(|due| DueDate::new(due.date(), None)),
+    }
+}
+
+/// Formats a date in the todo.txt format
+fn format_date(date: NaiveDateTime) -> String {
+    date.format(DATE_FORMAT).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2023, 6, day)
+            .unwrap()
+            .and_time(NaiveTime::MIN)
+    }
+
+    #[test]
+    fn import_works() {
+        let todo_txt =
+            "x 2023-06-11 2023-06-01 Write notes +Work_Stuff @laptop due:2023-06-10 pri:B\n\
+                        (A) 2023-06-02 Tag the release +Work_Stuff +Other\n\
+                        \n\
+                        Call mum @phone\n";
+        let board = import(todo_txt);
+
+        let names: Vec<String> = board.lists().iter().map(|list| list.name()).collect();
+        assert_eq!(names, vec!["Work Stuff", UNNAMED_LIST]);
+
+        let notes = board.lists()[0].get(0).unwrap();
+        assert!(notes.completed());
+        assert_eq!(notes.title(), "Write notes");
+        assert_eq!(notes.completed_at(), Some(date(11)));
+        assert_eq!(notes.created_at(), Some(date(1)));
+        assert_eq!(notes.priority(), Priority::High);
+        assert!(notes.tags().contains("laptop"));
+        assert_eq!(notes.due().unwrap().date(), date(10).date());
+
+        let release = board.lists()[0].get(1).unwrap();
+        assert!(!release.completed());
+        assert_eq!(release.priority(), Priority::Urgent);
+        assert_eq!(release.title(), "Tag the release +Other");
+
+        let call = board.lists()[1].get(0).unwrap();
+        assert_eq!(call.priority(), Priority::None);
+        assert!(call.tags().contains("phone"));
+    }
+
+    #[test]
+    fn round_trip_preserves_tasks() {
+        let todo_txt = "x 2023-06-11 2023-06-01 Write notes +Work @laptop due:2023-06-10 pri:B\n\
+                        (A) 2023-06-02 Tag the release +Work\n\
+                        Call mum +Todo @phone\n";
+        assert_eq!(export(&import(todo_txt)), todo_txt);
+    }
+
+    #[test]
+    fn round_trip_keeps_due_times() {
+        let todo_txt = "Send report +Work due:2023-06-10T17:00\n";
+        let board = import(todo_txt);
+        let due = board.lists()[0].get(0).unwrap().due().unwrap();
+        assert_eq!(due.date(), date(10).date());
+        assert_eq!(due.time(), NaiveTime::from_hms_opt(17, 0, 0));
+        assert_eq!(export(&board), todo_txt);
+    }
+
+    #[test]
+    fn round_trip_escapes_titles_that_look_like_fields() {
+        let titles = [
+            "x marks the spot",
+            "(A) is the best grade",
+            "2023-06-01 was a Thursday",
+            "Email bob@example.com about C++ and +1",
+            "Read about due:dates and pri:A",
+            "Keep \\backslashes",
+        ];
+        let mut board = Board::default();
+        let list = board.create_list("Work".to_string());
+        for title in titles {
+            list.add(Task::builder().title(title.to_string()).build().unwrap());
+        }
+
+        let todo_txt = export(&board);
+        assert!(todo_txt.starts_with("\\x marks the spot +Work\n"));
+        let imported = import(&todo_txt);
+        assert_eq!(imported.lists().len(), 1);
+        let imported_titles: Vec<String> = imported.lists()[0]
+            .iter()
+            .map(|task| task.title())
+            .collect();
+        assert_eq!(imported_titles, titles);
+        assert!(imported.lists()[0].iter().all(|task| !task.completed()
+            && task.priority() == Priority::None
+            && task.tags().is_empty()
+            && task.due().is_none()));
+    }
+}